@@ -0,0 +1,40 @@
+#[path = "../tests/common/mod.rs"]
+mod common;
+
+use base64::base64::{decode, encode};
+use common::random_bytes;
+use std::{hint::black_box, time::Instant};
+
+const SIZE: usize = 16 * 1024 * 1024;
+const ROUNDS: u32 = 20;
+
+fn throughput<T>(name: &str, bytes: usize, mut f: impl FnMut() -> T) {
+    // warm up the caches and the allocator
+    black_box(f());
+
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        black_box(f());
+    }
+    let elapsed = start.elapsed();
+
+    let mb = (bytes as f64 * ROUNDS as f64) / (1024.0 * 1024.0);
+    println!(
+        "{name:<8} {:>10.1} MB/s ({:?} per {} MiB)",
+        mb / elapsed.as_secs_f64(),
+        elapsed / ROUNDS,
+        bytes / (1024 * 1024)
+    );
+}
+
+fn main() {
+    let input = random_bytes(SIZE, 0x2545_F491_4F6C_DD1D);
+    let encoded = encode(&input);
+
+    assert_eq!(decode(&encoded).unwrap(), input);
+
+    throughput("encode", input.len(), || encode(black_box(&input)));
+    throughput("decode", encoded.len(), || {
+        decode(black_box(&encoded)).unwrap()
+    });
+}
@@ -0,0 +1,177 @@
+use anyhow::bail;
+
+/*
+* the alphabet and padding machinery shared by the codecs, the alphabets work for any
+* radix (base58 uses them too), but the padding only makes sense for the power of two
+* ones (base64, base32)
+*
+* a symbol carries log2(N) bits, and a block is the smallest number of symbols that
+* lines up with whole bytes again, which is what the padding fills up to:
+*
+*   base64: 6 bits, 4 symbols = 3 bytes
+*   base32: 5 bits, 8 symbols = 5 bytes
+*/
+
+pub(crate) const INVALID: u8 = 0xFF;
+pub const PAD: u8 = b'=';
+
+// the encode table maps a value to its symbol, and the decode table maps every
+// possible byte back to its value, or `INVALID` if it isn't part of the alphabet,
+// so both directions are a single array index
+pub struct Alphabet<const N: usize> {
+    pub(crate) encode: [u8; N],
+    pub(crate) decode: [u8; 256],
+}
+
+impl<const N: usize> Alphabet<N> {
+    pub const fn new(symbols: &[u8; N]) -> Self {
+        assert!(N <= 128, "the alphabet can't have more symbols than ascii");
+
+        let mut decode = [INVALID; 256];
+
+        let mut i = 0;
+        while i < symbols.len() {
+            let symbol = symbols[i];
+
+            assert!(symbol.is_ascii(), "the alphabet must be ascii");
+            assert!(
+                decode[symbol as usize] == INVALID,
+                "duplicate symbol in the alphabet"
+            );
+
+            decode[symbol as usize] = i as u8;
+            i += 1;
+        }
+
+        Alphabet {
+            encode: *symbols,
+            decode,
+        }
+    }
+
+    // decode `alias` the same as `symbol`, while still encoding to `symbol`
+    pub const fn with_alias(mut self, alias: u8, symbol: u8) -> Self {
+        assert!(alias.is_ascii(), "the alias must be ascii");
+        assert!(
+            self.decode[alias as usize] == INVALID,
+            "the alias is already a symbol"
+        );
+        assert!(
+            self.decode[symbol as usize] != INVALID,
+            "the aliased symbol isn't in the alphabet"
+        );
+
+        self.decode[alias as usize] = self.decode[symbol as usize];
+        self
+    }
+
+    // accept both cases of every letter in the alphabet when decoding
+    pub const fn case_insensitive(mut self) -> Self {
+        let mut i = 0;
+        while i < N {
+            let symbol = self.encode[i];
+            let other = if symbol.is_ascii_uppercase() {
+                symbol.to_ascii_lowercase()
+            } else {
+                symbol.to_ascii_uppercase()
+            };
+
+            if other != symbol && self.decode[other as usize] == INVALID {
+                self.decode[other as usize] = i as u8;
+            }
+
+            i += 1;
+        }
+
+        self
+    }
+
+    pub const fn symbols(&self) -> &[u8; N] {
+        &self.encode
+    }
+
+    pub const fn value(&self, symbol: u8) -> Option<u8> {
+        match self.decode[symbol as usize] {
+            INVALID => None,
+            value => Some(value),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct Config<const N: usize> {
+    pub alphabet: &'static Alphabet<N>,
+    pub padding: bool,
+}
+
+impl<const N: usize> Config<N> {
+    pub(crate) const BITS: usize = N.trailing_zeros() as usize;
+
+    // symbols (and bytes) per block, 8 / gcd(8, bits) and bits / gcd(8, bits)
+    pub(crate) const BLOCK_SYMBOLS: usize = match Self::BITS.trailing_zeros() {
+        shift @ 0..3 => 8 >> shift,
+        _ => 1,
+    };
+    pub(crate) const BLOCK_BYTES: usize = Self::BLOCK_SYMBOLS * Self::BITS / 8;
+
+    pub const fn new(alphabet: &'static Alphabet<N>, padding: bool) -> Self {
+        assert!(
+            N.is_power_of_two(),
+            "only power of two alphabets line up with bytes"
+        );
+
+        Config { alphabet, padding }
+    }
+
+    pub const fn with_padding(self, padding: bool) -> Self {
+        Config { padding, ..self }
+    }
+
+    // the exact number of symbols `input_len` bytes encode to, or `None` if that overflows
+    pub const fn encoded_len(&self, input_len: usize) -> Option<usize> {
+        let Some(full) = (input_len / Self::BLOCK_BYTES).checked_mul(Self::BLOCK_SYMBOLS) else {
+            return None;
+        };
+
+        match input_len % Self::BLOCK_BYTES {
+            0 => Some(full),
+            _ if self.padding => full.checked_add(Self::BLOCK_SYMBOLS),
+            rest => full.checked_add((rest * 8).div_ceil(Self::BITS)),
+        }
+    }
+
+    // validates the padding and the length, and returns how many of the symbols are data
+    pub(crate) fn data_len(&self, input: &[u8]) -> anyhow::Result<usize> {
+        let data_len = input.iter().rposition(|&b| b != PAD).map_or(0, |p| p + 1);
+        let padding = input.len() - data_len;
+
+        if self.padding {
+            if !input.len().is_multiple_of(Self::BLOCK_SYMBOLS) {
+                bail!(
+                    "the padded input length must be a multiple of {}, got {}",
+                    Self::BLOCK_SYMBOLS,
+                    input.len()
+                );
+            }
+
+            let expected =
+                (Self::BLOCK_SYMBOLS - data_len % Self::BLOCK_SYMBOLS) % Self::BLOCK_SYMBOLS;
+            if padding != expected {
+                bail!("expected {expected} padding symbols but got {padding}");
+            }
+        } else if padding > 0 {
+            bail!("unexpected padding at offset {data_len}");
+        }
+
+        // a trailing partial block is only valid if its leftover bits are less than a
+        // whole symbol, otherwise that symbol didn't carry any part of a byte
+        if (data_len % Self::BLOCK_SYMBOLS) * Self::BITS % 8 >= Self::BITS {
+            bail!(
+                "invalid length, {} symbols can't end a block",
+                data_len % Self::BLOCK_SYMBOLS
+            );
+        }
+
+        Ok(data_len)
+    }
+}
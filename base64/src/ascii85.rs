@@ -0,0 +1,177 @@
+use crate::alphabet;
+use anyhow::{anyhow, bail};
+
+/*
+* base85 turns every 4 bytes (a big endian u32) into 5 base 85 digits, since
+* 85^5 = 4437053125 is just above u32::MAX
+*
+*   "Man " -> 0x4d616e20 -> 24 73 82 80 78 -> "9jqo^"
+*
+* ascii85 writes digit + '!', so the symbols are '!' to 'u', and a group of four zero
+* bytes can be written as a single 'z'. adobe wraps the whole thing in <~ ~>, btoa doesn't,
+* and btoa 4.2 also writes a group of four spaces as a single 'y'.
+*
+* a trailing partial group of n bytes is padded with zeros, and only the first n + 1
+* symbols are written, the decoder pads it back with the highest digit ('u')
+*/
+
+pub type Alphabet = alphabet::Alphabet<85>;
+
+const fn ascii85_symbols() -> [u8; 85] {
+    let mut symbols = [0; 85];
+
+    let mut i = 0;
+    while i < symbols.len() {
+        symbols[i] = b'!' + i as u8;
+        i += 1;
+    }
+
+    symbols
+}
+
+pub const ALPHABET: Alphabet = Alphabet::new(&ascii85_symbols());
+
+const ZERO_GROUP: u8 = b'z';
+const SPACE_GROUP: u8 = b'y';
+const START: &str = "<~";
+const END: &str = "~>";
+
+#[derive(Clone, Copy)]
+pub struct Config {
+    pub delimiters: bool,
+    pub zero_shorthand: bool,
+    pub space_shorthand: bool,
+}
+
+pub const ADOBE: Config = Config {
+    delimiters: true,
+    zero_shorthand: true,
+    space_shorthand: false,
+};
+
+pub const BTOA: Config = Config {
+    delimiters: false,
+    zero_shorthand: true,
+    space_shorthand: true,
+};
+
+pub(crate) fn encode_group(group: [u8; 4], symbols: &[u8; 85]) -> [u8; 5] {
+    let mut n = u32::from_be_bytes(group);
+    let mut output = [0; 5];
+
+    for symbol in output.iter_mut().rev() {
+        *symbol = symbols[(n % 85) as usize];
+        n /= 85;
+    }
+
+    output
+}
+
+// `None` when the digits add up to more than a u32
+pub(crate) fn decode_group(values: [u8; 5]) -> Option<u32> {
+    values.iter().try_fold(0u32, |n, &value| {
+        n.checked_mul(85)?.checked_add(value as u32)
+    })
+}
+
+pub fn encode(input: &[u8]) -> String {
+    encode_with(input, &ADOBE)
+}
+
+pub fn encode_with(input: &[u8], config: &Config) -> String {
+    let mut output = String::with_capacity(input.len().div_ceil(4) * 5 + START.len() + END.len());
+
+    if config.delimiters {
+        output.push_str(START);
+    }
+
+    for chunk in input.chunks(4) {
+        if config.zero_shorthand && chunk == [0; 4] {
+            output.push(ZERO_GROUP as char);
+            continue;
+        }
+        if config.space_shorthand && chunk == *b"    " {
+            output.push(SPACE_GROUP as char);
+            continue;
+        }
+
+        let mut group = [0; 4];
+        group[..chunk.len()].copy_from_slice(chunk);
+
+        let symbols = encode_group(group, ALPHABET.symbols());
+        output.extend(symbols[..chunk.len() + 1].iter().map(|&s| s as char));
+    }
+
+    if config.delimiters {
+        output.push_str(END);
+    }
+
+    output
+}
+
+// the delimiters are optional, whitespace is ignored anywhere, and 'z' and 'y' are always
+// accepted
+pub fn decode(input: &str) -> anyhow::Result<Vec<u8>> {
+    let trimmed = input.trim();
+    let body = trimmed.strip_prefix(START).unwrap_or(trimmed);
+    let body = body.strip_suffix(END).unwrap_or(body);
+
+    let mut output = Vec::with_capacity(body.len() / 5 * 4 + 4);
+    let mut group = [0u8; 5];
+    let mut len = 0;
+
+    for (i, symbol) in body.bytes().enumerate() {
+        if symbol.is_ascii_whitespace() {
+            continue;
+        }
+
+        let shorthand = match symbol {
+            ZERO_GROUP => Some([0; 4]),
+            SPACE_GROUP => Some(*b"    "),
+            _ => None,
+        };
+
+        if let Some(bytes) = shorthand {
+            if len != 0 {
+                bail!(
+                    "{:?} in the middle of a group at offset {i}",
+                    char::from(symbol)
+                );
+            }
+
+            output.extend_from_slice(&bytes);
+            continue;
+        }
+
+        group[len] = ALPHABET.value(symbol).ok_or_else(|| {
+            anyhow!(
+                "invalid ascii85 symbol {:?} at offset {i}",
+                char::from(symbol)
+            )
+        })?;
+        len += 1;
+
+        if len == group.len() {
+            let n = decode_group(group)
+                .ok_or_else(|| anyhow!("the group ending at offset {i} overflows 32 bits"))?;
+
+            output.extend_from_slice(&n.to_be_bytes());
+            len = 0;
+        }
+    }
+
+    match len {
+        0 => {}
+        1 => bail!("a single trailing symbol can't encode a byte"),
+        _ => {
+            group[len..].fill(84);
+
+            let n =
+                decode_group(group).ok_or_else(|| anyhow!("the last group overflows 32 bits"))?;
+
+            output.extend_from_slice(&n.to_be_bytes()[..len - 1]);
+        }
+    }
+
+    Ok(output)
+}
@@ -0,0 +1,164 @@
+use crate::{
+    alphabet::{self, PAD},
+    bits,
+};
+use anyhow::{anyhow, bail};
+
+/*
+* RFC 4648 base32, 5 bits per symbol, 8 symbols for every 5 bytes
+*
+*   01110010 01100101 01110011 01110100 01110011
+*
+*   01110 01001 10010 10111 00110 11101 00011 10011
+*
+* and Crockford's variant, which uses its own alphabet, no padding, and folds the
+* symbols that are easy to mistake for each other (O -> 0, I and L -> 1)
+*/
+
+pub type Alphabet = alphabet::Alphabet<32>;
+pub type Config = alphabet::Config<32>;
+
+pub const STANDARD_ALPHABET: Alphabet =
+    Alphabet::new(b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567").case_insensitive();
+
+pub const HEX_ALPHABET: Alphabet =
+    Alphabet::new(b"0123456789ABCDEFGHIJKLMNOPQRSTUV").case_insensitive();
+
+pub const CROCKFORD_ALPHABET: Alphabet = Alphabet::new(b"0123456789ABCDEFGHJKMNPQRSTVWXYZ")
+    .case_insensitive()
+    .with_alias(b'O', b'0')
+    .with_alias(b'o', b'0')
+    .with_alias(b'I', b'1')
+    .with_alias(b'i', b'1')
+    .with_alias(b'L', b'1')
+    .with_alias(b'l', b'1');
+
+pub const STANDARD: Config = Config::new(&STANDARD_ALPHABET, true);
+pub const STANDARD_NO_PAD: Config = Config::new(&STANDARD_ALPHABET, false);
+pub const HEX: Config = Config::new(&HEX_ALPHABET, true);
+pub const HEX_NO_PAD: Config = Config::new(&HEX_ALPHABET, false);
+pub const CROCKFORD: Config = Config::new(&CROCKFORD_ALPHABET, false);
+
+// the check symbol is the value mod 37, and the 5 values above 31 get their own symbols
+const CROCKFORD_CHECK_SYMBOLS: &[u8; 37] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ*~$=U";
+
+pub fn encoded_len(input_len: usize, padding: bool) -> Option<usize> {
+    STANDARD.with_padding(padding).encoded_len(input_len)
+}
+
+pub fn encode(input: &[u8]) -> String {
+    encode_with(input, &STANDARD)
+}
+
+pub fn encode_with(input: &[u8], config: &Config) -> String {
+    let len = config
+        .encoded_len(input.len())
+        .expect("the encoded length overflows usize");
+
+    let mut values = Vec::with_capacity(len);
+    bits::regroup(input, 8, 5, true, &mut values).expect("bytes always fit in 8 bits");
+
+    let mut output: Vec<u8> = values
+        .into_iter()
+        .map(|value| config.alphabet.symbols()[value as usize])
+        .collect();
+
+    output.resize(len, PAD);
+
+    String::from_utf8(output).expect("the alphabet is always ascii")
+}
+
+fn invalid_symbol(symbol: u8, offset: usize) -> anyhow::Error {
+    anyhow!(
+        "invalid base32 symbol {:?} at offset {offset}",
+        char::from(symbol)
+    )
+}
+
+pub fn decode(input: &str) -> anyhow::Result<Vec<u8>> {
+    decode_with(input, &STANDARD)
+}
+
+pub fn decode_with(input: &str, config: &Config) -> anyhow::Result<Vec<u8>> {
+    let input = input.as_bytes();
+    let data_len = config.data_len(input)?;
+
+    let values = input[..data_len]
+        .iter()
+        .enumerate()
+        .map(|(i, &symbol)| {
+            config
+                .alphabet
+                .value(symbol)
+                .ok_or_else(|| invalid_symbol(symbol, i))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    // the leftover bits are only the zero padding of the last byte, like with base64
+    // they aren't required to be zero
+    let mut output = Vec::with_capacity(data_len * 5 / 8);
+    bits::regroup(&values, 5, 8, false, &mut output)?;
+
+    Ok(output)
+}
+
+// the input read as one big endian number, mod 37
+fn crockford_check(input: &[u8]) -> u8 {
+    input
+        .iter()
+        .fold(0u32, |check, &b| (check * 256 + b as u32) % 37) as u8
+}
+
+// with `check` a check symbol over the input bytes is appended
+pub fn encode_crockford(input: &[u8], check: bool) -> String {
+    let mut output = encode_with(input, &CROCKFORD);
+
+    if check {
+        output.push(CROCKFORD_CHECK_SYMBOLS[crockford_check(input) as usize] as char);
+    }
+
+    output
+}
+
+// hyphens are only there for readability and get ignored, and with `check` the last
+// symbol has to be the check symbol of the decoded bytes
+pub fn decode_crockford(input: &str, check: bool) -> anyhow::Result<Vec<u8>> {
+    let mut symbols: Vec<u8> = input.bytes().filter(|&b| b != b'-').collect();
+
+    let expected_check = if check {
+        let symbol = symbols
+            .pop()
+            .ok_or_else(|| anyhow!("the input is missing the check symbol"))?;
+
+        let value = match CROCKFORD_CHECK_SYMBOLS[32..]
+            .iter()
+            .position(|&s| s.eq_ignore_ascii_case(&symbol))
+        {
+            Some(i) => 32 + i as u8,
+            None => CROCKFORD_ALPHABET
+                .value(symbol)
+                .ok_or_else(|| anyhow!("invalid check symbol {:?}", char::from(symbol)))?,
+        };
+
+        Some(value)
+    } else {
+        None
+    };
+
+    let symbols = String::from_utf8(symbols)?;
+    let output = decode_with(&symbols, &CROCKFORD)?;
+
+    if let Some(expected) = expected_check {
+        let actual = crockford_check(&output);
+
+        if actual != expected {
+            bail!(
+                "check symbol mismatch, the input ends with {:?} but the data checks to {:?}",
+                char::from(CROCKFORD_CHECK_SYMBOLS[expected as usize]),
+                char::from(CROCKFORD_CHECK_SYMBOLS[actual as usize])
+            );
+        }
+    }
+
+    Ok(output)
+}
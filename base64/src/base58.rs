@@ -0,0 +1,145 @@
+use crate::{alphabet, hex, sha256};
+use anyhow::{anyhow, bail};
+
+/*
+* base58 treats the whole input as one big endian number and writes it in base 58,
+* so unlike base64 there are no blocks, and every byte affects every symbol
+*
+* leading zero bytes would disappear in the number, so each one is written as the
+* first symbol of the alphabet ('1' for bitcoin), that's how "1..." addresses come about
+*
+* base58check appends the first 4 bytes of sha256(sha256(input)) before encoding
+*/
+
+pub type Alphabet = alphabet::Alphabet<58>;
+
+pub const BITCOIN_ALPHABET: Alphabet =
+    Alphabet::new(b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz");
+
+pub const RIPPLE_ALPHABET: Alphabet =
+    Alphabet::new(b"rpshnaf39wBUDNEGHJKLM4PQRST7VWXYZ2bcdeCg65jkm8oFqi1tuvAxyz");
+
+pub const FLICKR_ALPHABET: Alphabet =
+    Alphabet::new(b"123456789abcdefghijkmnopqrstuvwxyzABCDEFGHJKLMNPQRSTUVWXYZ");
+
+const CHECKSUM_LEN: usize = 4;
+
+pub fn encode(input: &[u8]) -> String {
+    encode_with(input, &BITCOIN_ALPHABET)
+}
+
+pub fn encode_with(input: &[u8], alphabet: &Alphabet) -> String {
+    let zeros = input.iter().take_while(|&&b| b == 0).count();
+
+    // log(256) / log(58) is about 1.37 symbols per byte
+    let mut digits: Vec<u8> = Vec::with_capacity((input.len() - zeros) * 138 / 100 + 1);
+
+    // the digits are little endian, every byte multiplies the number so far by 256
+    for &b in &input[zeros..] {
+        let mut carry = b as u32;
+
+        for digit in digits.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+
+    let symbols = alphabet.symbols();
+
+    std::iter::repeat_n(symbols[0], zeros)
+        .chain(digits.iter().rev().map(|&digit| symbols[digit as usize]))
+        .map(char::from)
+        .collect()
+}
+
+pub fn decode(input: &str) -> anyhow::Result<Vec<u8>> {
+    decode_with(input, &BITCOIN_ALPHABET)
+}
+
+pub fn decode_with(input: &str, alphabet: &Alphabet) -> anyhow::Result<Vec<u8>> {
+    let zero = alphabet.symbols()[0];
+    let input = input.as_bytes();
+    let zeros = input.iter().take_while(|&&b| b == zero).count();
+
+    // log(58) / log(256) is about 0.733 bytes per symbol
+    let mut bytes: Vec<u8> = Vec::with_capacity((input.len() - zeros) * 733 / 1000 + 1);
+
+    // the reverse of encoding, every symbol multiplies the number so far by 58
+    for (i, &symbol) in input.iter().enumerate().skip(zeros) {
+        let mut carry = alphabet.value(symbol).ok_or_else(|| {
+            anyhow!(
+                "invalid base58 symbol {:?} at offset {i}",
+                char::from(symbol)
+            )
+        })? as u32;
+
+        for byte in bytes.iter_mut() {
+            carry += *byte as u32 * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+
+    Ok(std::iter::repeat_n(0, zeros)
+        .chain(bytes.into_iter().rev())
+        .collect())
+}
+
+fn checksum(input: &[u8]) -> [u8; CHECKSUM_LEN] {
+    let hash = sha256::digest(&sha256::digest(input));
+    hash[..CHECKSUM_LEN].try_into().unwrap()
+}
+
+// `input` is everything that gets checksummed, so for bitcoin that's the version
+// byte followed by the payload
+pub fn encode_check(input: &[u8]) -> String {
+    encode_check_with(input, &BITCOIN_ALPHABET)
+}
+
+pub fn encode_check_with(input: &[u8], alphabet: &Alphabet) -> String {
+    let mut data = Vec::with_capacity(input.len() + CHECKSUM_LEN);
+    data.extend_from_slice(input);
+    data.extend_from_slice(&checksum(input));
+
+    encode_with(&data, alphabet)
+}
+
+// verifies the checksum, and returns the data without it
+pub fn decode_check(input: &str) -> anyhow::Result<Vec<u8>> {
+    decode_check_with(input, &BITCOIN_ALPHABET)
+}
+
+pub fn decode_check_with(input: &str, alphabet: &Alphabet) -> anyhow::Result<Vec<u8>> {
+    let mut data = decode_with(input, alphabet)?;
+
+    if data.len() < CHECKSUM_LEN {
+        bail!(
+            "the decoded data is {} bytes, too short to hold a {CHECKSUM_LEN} byte checksum",
+            data.len()
+        );
+    }
+
+    let expected = data.split_off(data.len() - CHECKSUM_LEN);
+    let actual = checksum(&data);
+
+    if expected != actual {
+        bail!(
+            "checksum mismatch, the input carries {} but the data hashes to {}",
+            hex::encode(&expected),
+            hex::encode(&actual)
+        );
+    }
+
+    Ok(data)
+}
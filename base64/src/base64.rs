@@ -0,0 +1,425 @@
+use crate::alphabet::{self, INVALID, PAD};
+use anyhow::{anyhow, bail};
+
+/*
+*Value 	Char 	  	Value 	Char 	  	Value 	Char 	  	Value 	Char
+0 	A 	  	16 	Q 	  	32 	g 	  	48 	w
+1 	B 	  	17 	R 	  	33 	h 	  	49 	x
+2 	C 	  	18 	S 	  	34 	i 	  	50 	y
+3 	D 	  	19 	T 	  	35 	j 	  	51 	z
+4 	E 	  	20 	U 	  	36 	k 	  	52 	0
+5 	F 	  	21 	V 	  	37 	l 	  	53 	1
+6 	G 	  	22 	W 	  	38 	m 	  	54 	2
+7 	H 	  	23 	X 	  	39 	n 	  	55 	3
+8 	I 	  	24 	Y 	  	40 	o 	  	56 	4
+9 	J 	  	25 	Z 	  	41 	p 	  	57 	5
+10 	K 	  	26 	a 	  	42 	q 	  	58 	6
+11 	L 	  	27 	b 	  	43 	r 	  	59 	7
+12 	M 	  	28 	c 	  	44 	s 	  	60 	8
+13 	N 	  	29 	d 	  	45 	t 	  	61 	9
+14 	O 	  	30 	e 	  	46 	u 	  	62 	+
+15 	P 	  	31 	f 	  	47 	v 	  	63 	/
+*
+*/
+
+pub type Alphabet = alphabet::Alphabet<64>;
+pub type Config = alphabet::Config<64>;
+
+pub const STANDARD_ALPHABET: Alphabet =
+    Alphabet::new(b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/");
+
+pub const URL_SAFE_ALPHABET: Alphabet =
+    Alphabet::new(b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_");
+
+pub const STANDARD: Config = Config::new(&STANDARD_ALPHABET, true);
+pub const STANDARD_NO_PAD: Config = Config::new(&STANDARD_ALPHABET, false);
+pub const URL_SAFE: Config = Config::new(&URL_SAFE_ALPHABET, true);
+pub const URL_SAFE_NO_PAD: Config = Config::new(&URL_SAFE_ALPHABET, false);
+
+#[inline]
+fn get_first_chunk(byte: u8) -> u8 {
+    (byte >> 2) & 0b111111
+}
+
+#[inline]
+fn get_second_chunk(byte1: u8, byte2: u8) -> u8 {
+    (byte1 << 4 | byte2 >> 4) & 0b111111
+}
+
+#[inline]
+fn get_third_chunk(byte2: u8, byte3: u8) -> u8 {
+    (((byte2 & 0b1111) << 2) | byte3 >> 6) & 0b111111
+}
+
+#[inline]
+fn get_fourth_chunk(byte3: u8) -> u8 {
+    byte3 & 0b111111
+}
+
+// the exact number of symbols `input_len` bytes encode to, or `None` if that overflows
+pub fn encoded_len(input_len: usize, padding: bool) -> Option<usize> {
+    STANDARD.with_padding(padding).encoded_len(input_len)
+}
+
+// an upper bound on the number of bytes `input_len` symbols decode to, it's exact
+// when the input is unpadded, but padding and invalid input can make it smaller
+pub fn decoded_len_estimate(input_len: usize) -> usize {
+    input_len / 4 * 3 + (input_len % 4) * 6 / 8
+}
+
+fn encode_into(input: &[u8], output: &mut [u8], config: &Config) -> usize {
+    let table = &config.alphabet.encode;
+    let mut i = 0;
+    let mut o = 0;
+
+    // a big endian u64 load holds 6 whole bytes (8 sextets) in its top 48 bits,
+    // the 2 extra bytes are only peeked at and get encoded by the next load
+    while i + 8 <= input.len() {
+        let n = u64::from_be_bytes(input[i..i + 8].try_into().unwrap());
+
+        for (k, symbol) in output[o..o + 8].iter_mut().enumerate() {
+            *symbol = table[((n >> (58 - 6 * k)) & 0b111111) as usize];
+        }
+
+        i += 6;
+        o += 8;
+    }
+
+    for chunk in input[i..].chunks(3) {
+        /*
+            011100 10
+            0110 0101
+            01 110011
+
+            011100
+            100110
+            010101
+            110011
+        */
+
+        let sextets = match *chunk {
+            [b1, b2, b3] => [
+                get_first_chunk(b1),
+                get_second_chunk(b1, b2),
+                get_third_chunk(b2, b3),
+                get_fourth_chunk(b3),
+            ],
+            [b1, b2] => [
+                get_first_chunk(b1),
+                get_second_chunk(b1, b2),
+                get_third_chunk(b2, 0),
+                0,
+            ],
+            [b1] => [get_first_chunk(b1), get_second_chunk(b1, 0), 0, 0],
+            _ => unreachable!(),
+        };
+
+        for &sextet in &sextets[..chunk.len() + 1] {
+            output[o] = table[sextet as usize];
+            o += 1;
+        }
+
+        if config.padding {
+            for _ in chunk.len()..3 {
+                output[o] = PAD;
+                o += 1;
+            }
+        }
+    }
+
+    o
+}
+
+pub fn encode(input: &[u8]) -> String {
+    encode_with(input, &STANDARD)
+}
+
+pub fn encode_with(input: &[u8], config: &Config) -> String {
+    let len = config
+        .encoded_len(input.len())
+        .expect("the encoded length overflows usize");
+
+    let mut output = vec![0; len];
+    let written = encode_into(input, &mut output, config);
+    debug_assert_eq!(written, output.len());
+
+    String::from_utf8(output).expect("the alphabet is always ascii")
+}
+
+pub fn encode_slice(input: &[u8], output: &mut [u8]) -> anyhow::Result<usize> {
+    encode_slice_with(input, output, &STANDARD)
+}
+
+// encodes into the start of `output` without allocating, and returns how many
+// symbols were written
+pub fn encode_slice_with(
+    input: &[u8],
+    output: &mut [u8],
+    config: &Config,
+) -> anyhow::Result<usize> {
+    let needed = config
+        .encoded_len(input.len())
+        .ok_or_else(|| anyhow!("the encoded length overflows usize"))?;
+
+    if output.len() < needed {
+        bail!(
+            "the output buffer is too small, need {needed} bytes but got {}",
+            output.len()
+        );
+    }
+
+    Ok(encode_into(input, output, config))
+}
+
+fn invalid_symbol(symbol: u8, offset: usize) -> anyhow::Error {
+    anyhow!(
+        "invalid base64 symbol {:?} at offset {offset}",
+        char::from(symbol)
+    )
+}
+
+// decodes a block of 8 symbols, or the 2 to 4 symbols at the end of the input,
+// on failure it returns the index of the first invalid symbol in the block
+#[inline(always)]
+fn decode_block(symbols: &[u8], output: &mut [u8], table: &[u8; 256]) -> Result<usize, usize> {
+    /*
+        011100
+        10 0110
+        0101 01
+        110011
+
+        01110010
+        01100101
+        01110011
+    */
+
+    let mut n = 0u64;
+    let mut invalid = 0u8;
+
+    for &symbol in symbols {
+        let value = table[symbol as usize];
+        invalid |= value;
+        n = n << 6 | value as u64;
+    }
+
+    // valid values never go above 63, so the high bit only comes from `INVALID`
+    if invalid & 0x80 != 0 {
+        return Err(symbols
+            .iter()
+            .position(|&symbol| table[symbol as usize] == INVALID)
+            .unwrap());
+    }
+
+    // left align the bits, so the decoded bytes are the first ones of the big endian
+    // representation, the leftover bits of a partial block are dropped
+    let bits = symbols.len() * 6;
+    let bytes = bits / 8;
+    n <<= 64 - bits;
+
+    output[..bytes].copy_from_slice(&n.to_be_bytes()[..bytes]);
+
+    Ok(bytes)
+}
+
+fn check_output_len(data_len: usize, output_len: usize) -> anyhow::Result<()> {
+    let needed = decoded_len_estimate(data_len);
+
+    if output_len < needed {
+        bail!("the output buffer is too small, need {needed} bytes but got {output_len}");
+    }
+
+    Ok(())
+}
+
+fn decode_into(input: &[u8], output: &mut [u8], config: &Config) -> anyhow::Result<usize> {
+    let data_len = config.data_len(input)?;
+    check_output_len(data_len, output.len())?;
+
+    let table = &config.alphabet.decode;
+    let mut i = 0;
+    let mut o = 0;
+
+    // 8 symbols are 48 bits, which are exactly 6 bytes
+    while i + 8 <= data_len {
+        o += decode_block(&input[i..i + 8], &mut output[o..], table)
+            .map_err(|k| invalid_symbol(input[i + k], i + k))?;
+        i += 8;
+    }
+
+    while i < data_len {
+        let len = (data_len - i).min(4);
+        o += decode_block(&input[i..i + len], &mut output[o..], table)
+            .map_err(|k| invalid_symbol(input[i + k], i + k))?;
+        i += len;
+    }
+
+    Ok(o)
+}
+
+pub fn decode(input: &str) -> anyhow::Result<Vec<u8>> {
+    decode_with(input, &STANDARD)
+}
+
+pub fn decode_with(input: &str, config: &Config) -> anyhow::Result<Vec<u8>> {
+    let mut output = vec![0; decoded_len_estimate(input.len())];
+    let written = decode_into(input.as_bytes(), &mut output, config)?;
+    output.truncate(written);
+
+    Ok(output)
+}
+
+pub fn decode_slice(input: &[u8], output: &mut [u8]) -> anyhow::Result<usize> {
+    decode_slice_with(input, output, &STANDARD)
+}
+
+// decodes into the start of `output` without allocating, and returns how many
+// bytes were written, `decoded_len_estimate` is always enough room
+pub fn decode_slice_with(
+    input: &[u8],
+    output: &mut [u8],
+    config: &Config,
+) -> anyhow::Result<usize> {
+    decode_into(input, output, config)
+}
+
+pub fn decode_in_place(buffer: &mut [u8]) -> anyhow::Result<usize> {
+    decode_in_place_with(buffer, &STANDARD)
+}
+
+// decodes the symbols in `buffer` into its own start and returns the decoded length,
+// every block is read before it's written and the output never catches up with the
+// input (6 bytes for every 8 symbols), so nothing is overwritten before it's read
+pub fn decode_in_place_with(buffer: &mut [u8], config: &Config) -> anyhow::Result<usize> {
+    let data_len = config.data_len(buffer)?;
+
+    let table = &config.alphabet.decode;
+    let mut block = [0u8; 8];
+    let mut i = 0;
+    let mut o = 0;
+
+    while i < data_len {
+        let len = if data_len - i >= 8 {
+            8
+        } else {
+            (data_len - i).min(4)
+        };
+        block[..len].copy_from_slice(&buffer[i..i + len]);
+
+        o += decode_block(&block[..len], &mut buffer[o..], table)
+            .map_err(|k| invalid_symbol(block[k], i + k))?;
+        i += len;
+    }
+
+    Ok(o)
+}
+
+/*
+* constant time decoding, for keys, tokens and anything else that's secret
+*
+* the table lookup above indexes memory with the symbol and bails out on the first
+* invalid one, both of which depend on the secret, so the timing (and the cache)
+* can leak it. here every symbol is compared against the whole alphabet with masks
+* and the invalid symbols are only collected into a flag that's checked at the end.
+*
+* only the length of the input and the trailing padding are branched on, and those
+* are public anyway, since they're visible from the length of the encoded data
+*/
+
+// 0xFF when `a == b` and 0x00 otherwise, without a branch
+#[inline(always)]
+fn ct_eq_mask(a: u8, b: u8) -> u8 {
+    let x = (a ^ b) as u16;
+    (x.wrapping_sub(1) >> 8) as u8
+}
+
+// returns the value of the symbol, and a 0xFF mask if it's in the alphabet
+#[inline(always)]
+fn ct_value(symbol: u8, alphabet: &Alphabet) -> (u8, u8) {
+    let mut value = 0;
+    let mut found = 0;
+
+    for (i, &candidate) in alphabet.symbols().iter().enumerate() {
+        let mask = ct_eq_mask(symbol, candidate);
+        value |= mask & i as u8;
+        found |= mask;
+    }
+
+    (value, found)
+}
+
+// same as `decode_block`, but instead of failing it returns 0xFF if any symbol was invalid
+#[inline(always)]
+fn decode_block_ct(symbols: &[u8], output: &mut [u8], alphabet: &Alphabet) -> (usize, u8) {
+    let mut n = 0u64;
+    let mut invalid = 0u8;
+
+    for &symbol in symbols {
+        let (value, found) = ct_value(symbol, alphabet);
+        invalid |= !found;
+        n = n << 6 | value as u64;
+    }
+
+    let bits = symbols.len() * 6;
+    let bytes = bits / 8;
+    n <<= 64 - bits;
+
+    output[..bytes].copy_from_slice(&n.to_be_bytes()[..bytes]);
+
+    (bytes, invalid)
+}
+
+fn decode_ct_into(input: &[u8], output: &mut [u8], config: &Config) -> anyhow::Result<usize> {
+    let data_len = config.data_len(input)?;
+    check_output_len(data_len, output.len())?;
+
+    let mut invalid = 0u8;
+    let mut i = 0;
+    let mut o = 0;
+
+    while i < data_len {
+        let len = if data_len - i >= 8 {
+            8
+        } else {
+            (data_len - i).min(4)
+        };
+
+        let (written, block_invalid) =
+            decode_block_ct(&input[i..i + len], &mut output[o..], config.alphabet);
+
+        invalid |= block_invalid;
+        i += len;
+        o += written;
+    }
+
+    if invalid != 0 {
+        // don't leave a partially decoded secret behind
+        output[..o].fill(0);
+        bail!("invalid base64 symbol in the input");
+    }
+
+    Ok(o)
+}
+
+pub fn decode_ct(input: &str) -> anyhow::Result<Vec<u8>> {
+    decode_ct_with(input, &STANDARD)
+}
+
+pub fn decode_ct_with(input: &str, config: &Config) -> anyhow::Result<Vec<u8>> {
+    let mut output = vec![0; decoded_len_estimate(input.len())];
+    let written = decode_ct_into(input.as_bytes(), &mut output, config)?;
+    output.truncate(written);
+
+    Ok(output)
+}
+
+pub fn decode_ct_slice(input: &[u8], output: &mut [u8]) -> anyhow::Result<usize> {
+    decode_ct_slice_with(input, output, &STANDARD)
+}
+
+pub fn decode_ct_slice_with(
+    input: &[u8],
+    output: &mut [u8],
+    config: &Config,
+) -> anyhow::Result<usize> {
+    decode_ct_into(input, output, config)
+}
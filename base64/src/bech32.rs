@@ -0,0 +1,298 @@
+use crate::{alphabet, bits};
+use anyhow::{anyhow, bail};
+
+/*
+* BIP-173 bech32 and BIP-350 bech32m, a human readable part, a '1' separator, and the
+* data as 5 bit symbols followed by a 6 symbol BCH checksum:
+*
+*   bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4
+*   ^^ ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
+*   hrp             data + checksum
+*
+* the checksum covers the hrp too, and detects any 4 substituted symbols. bech32m only
+* differs in the constant the checksum is xored with, it fixes bech32 not noticing
+* 'q's inserted or removed right before a trailing 'p'
+*
+* the hrp is only the last '1' away from the data, so it may contain '1's itself
+*
+* segwit addresses don't regroup the whole data part, the first value is the witness
+* version on its own and only the program after it is bytes. version 0 is bech32 with
+* a program of 20 or 32 bytes, every later version is bech32m
+*/
+
+pub type Alphabet = alphabet::Alphabet<32>;
+
+pub const ALPHABET: Alphabet =
+    Alphabet::new(b"qpzry9x8gf2tvdw0s3jn54khce6mua7l").case_insensitive();
+
+const SEPARATOR: char = '1';
+const CHECKSUM_LEN: usize = 6;
+const MAX_LEN: usize = 90;
+const MAX_HRP_LEN: usize = 83;
+
+const MAX_WITNESS_VERSION: u8 = 16;
+const WITNESS_PROGRAM_LEN: std::ops::RangeInclusive<usize> = 2..=40;
+
+const GENERATOR: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Variant {
+    Bech32,
+    Bech32m,
+}
+
+impl Variant {
+    const fn constant(self) -> u32 {
+        match self {
+            Variant::Bech32 => 1,
+            Variant::Bech32m => 0x2bc830a3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bech32 {
+    pub hrp: String,
+    // the 5 bit values between the separator and the checksum
+    pub values: Vec<u8>,
+    pub variant: Variant,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Witness {
+    pub version: u8,
+    pub program: Vec<u8>,
+}
+
+impl Witness {
+    pub const fn variant(&self) -> Variant {
+        match self.version {
+            0 => Variant::Bech32,
+            _ => Variant::Bech32m,
+        }
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        let len = self.program.len();
+
+        if self.version > MAX_WITNESS_VERSION {
+            bail!(
+                "invalid witness version {}, the highest is {MAX_WITNESS_VERSION}",
+                self.version
+            );
+        }
+        if !WITNESS_PROGRAM_LEN.contains(&len) {
+            bail!("a witness program is 2 to 40 bytes, got {len}");
+        }
+        if self.version == 0 && len != 20 && len != 32 {
+            bail!("a version 0 witness program is 20 or 32 bytes, got {len}");
+        }
+
+        Ok(())
+    }
+}
+
+// 5 bit values regrouped back into bytes, the padding has to be less than 5 zero bits
+fn to_bytes(values: &[u8]) -> anyhow::Result<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(values.len() * 5 / 8);
+
+    match bits::regroup(values, 5, 8, false, &mut bytes)? {
+        (bits, _) if bits >= 5 => bail!("{bits} bits of padding, a whole symbol too many"),
+        (_, value) if value != 0 => bail!("the padding bits aren't zero"),
+        _ => Ok(bytes),
+    }
+}
+
+impl Bech32 {
+    pub fn bytes(&self) -> anyhow::Result<Vec<u8>> {
+        to_bytes(&self.values)
+    }
+
+    // the data part read as a segwit address
+    pub fn witness(&self) -> anyhow::Result<Witness> {
+        let (&version, program) = self
+            .values
+            .split_first()
+            .ok_or_else(|| anyhow!("the data part is empty, it has no witness version"))?;
+
+        let witness = Witness {
+            version,
+            program: to_bytes(program)?,
+        };
+        witness.validate()?;
+
+        if self.variant != witness.variant() {
+            bail!(
+                "a version {version} witness uses the {:?} checksum, not {:?}",
+                witness.variant(),
+                self.variant
+            );
+        }
+
+        Ok(witness)
+    }
+}
+
+// the BCH code's remainder, computed over GF(32) one symbol at a time
+fn polymod(values: impl IntoIterator<Item = u8>) -> u32 {
+    let mut checksum = 1u32;
+
+    for value in values {
+        let top = checksum >> 25;
+        checksum = (checksum & 0x1ffffff) << 5 ^ value as u32;
+
+        for (i, generator) in GENERATOR.iter().enumerate() {
+            if top >> i & 1 == 1 {
+                checksum ^= generator;
+            }
+        }
+    }
+
+    checksum
+}
+
+// the hrp goes into the checksum as the high bits of every character, a zero, and
+// then the low bits of every character
+fn expand_hrp(hrp: &str) -> impl Iterator<Item = u8> + '_ {
+    let hrp = hrp.as_bytes();
+
+    hrp.iter()
+        .map(|&c| c >> 5)
+        .chain([0])
+        .chain(hrp.iter().map(|&c| c & 31))
+}
+
+fn checksum(hrp: &str, values: &[u8], variant: Variant) -> [u8; CHECKSUM_LEN] {
+    let remainder = polymod(
+        expand_hrp(hrp)
+            .chain(values.iter().copied())
+            .chain([0; CHECKSUM_LEN]),
+    ) ^ variant.constant();
+
+    std::array::from_fn(|i| (remainder >> (5 * (CHECKSUM_LEN - 1 - i)) & 31) as u8)
+}
+
+fn validate_hrp(hrp: &str) -> anyhow::Result<()> {
+    if hrp.is_empty() || hrp.len() > MAX_HRP_LEN {
+        bail!(
+            "the human readable part must be 1 to {MAX_HRP_LEN} characters, got {}",
+            hrp.len()
+        );
+    }
+
+    if let Some(c) = hrp.chars().find(|c| !('!'..='~').contains(c)) {
+        bail!("invalid character {c:?} in the human readable part");
+    }
+
+    Ok(())
+}
+
+pub fn encode(hrp: &str, input: &[u8], variant: Variant) -> anyhow::Result<String> {
+    let mut values = Vec::new();
+    bits::regroup(input, 8, 5, true, &mut values)?;
+
+    encode_values(hrp, &values, variant)
+}
+
+pub fn encode_segwit(hrp: &str, witness: &Witness) -> anyhow::Result<String> {
+    witness.validate()?;
+
+    let mut values = vec![witness.version];
+    bits::regroup(&witness.program, 8, 5, true, &mut values)?;
+
+    encode_values(hrp, &values, witness.variant())
+}
+
+// `values` are 5 bit values, `encode_segwit` has the witness version as the first one
+pub fn encode_values(hrp: &str, values: &[u8], variant: Variant) -> anyhow::Result<String> {
+    validate_hrp(hrp)?;
+
+    let len = hrp.len() + 1 + values.len() + CHECKSUM_LEN;
+    if len > MAX_LEN {
+        bail!("the encoded string would be {len} characters, more than the {MAX_LEN} allowed");
+    }
+
+    // mixed case isn't allowed, and lowercase is the canonical form
+    let hrp = hrp.to_ascii_lowercase();
+    let symbols = ALPHABET.symbols();
+
+    let mut output = String::with_capacity(len);
+    output.push_str(&hrp);
+    output.push(SEPARATOR);
+
+    for (i, &value) in values.iter().enumerate() {
+        let symbol = symbols
+            .get(value as usize)
+            .ok_or_else(|| anyhow!("the value {value} at offset {i} doesn't fit in 5 bits"))?;
+        output.push(*symbol as char);
+    }
+
+    let checksum = checksum(&hrp, values, variant);
+    output.extend(
+        checksum
+            .iter()
+            .map(|&value| symbols[value as usize] as char),
+    );
+
+    Ok(output)
+}
+
+// accepts either variant, the checksum tells which one it is
+pub fn decode(input: &str) -> anyhow::Result<Bech32> {
+    if input.len() > MAX_LEN {
+        bail!(
+            "{} characters is longer than the {MAX_LEN} allowed",
+            input.len()
+        );
+    }
+
+    let has_lower = input.bytes().any(|b| b.is_ascii_lowercase());
+    let has_upper = input.bytes().any(|b| b.is_ascii_uppercase());
+    if has_lower && has_upper {
+        bail!("mixed case isn't allowed");
+    }
+
+    let input = input.to_ascii_lowercase();
+
+    let (hrp, data) = input
+        .rsplit_once(SEPARATOR)
+        .ok_or_else(|| anyhow!("missing the '{SEPARATOR}' separator"))?;
+
+    validate_hrp(hrp)?;
+
+    if data.len() < CHECKSUM_LEN {
+        bail!(
+            "the data part is {} symbols, too short to hold a {CHECKSUM_LEN} symbol checksum",
+            data.len()
+        );
+    }
+
+    let mut values = data
+        .bytes()
+        .enumerate()
+        .map(|(i, symbol)| {
+            ALPHABET.value(symbol).ok_or_else(|| {
+                anyhow!(
+                    "invalid bech32 symbol {:?} at offset {}",
+                    char::from(symbol),
+                    hrp.len() + 1 + i
+                )
+            })
+        })
+        .collect::<anyhow::Result<Vec<u8>>>()?;
+
+    let remainder = polymod(expand_hrp(hrp).chain(values.iter().copied()));
+
+    let variant = [Variant::Bech32, Variant::Bech32m]
+        .into_iter()
+        .find(|variant| variant.constant() == remainder)
+        .ok_or_else(|| anyhow!("checksum mismatch"))?;
+
+    values.truncate(values.len() - CHECKSUM_LEN);
+
+    Ok(Bech32 {
+        hrp: hrp.to_string(),
+        values,
+        variant,
+    })
+}
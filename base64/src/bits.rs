@@ -0,0 +1,47 @@
+use anyhow::bail;
+
+// regroups `from` bit wide values into `to` bit wide values, most significant bit first
+// (BIP-173 calls this `convertbits`), both widths have to be between 1 and 8
+//
+// with `pad` the last partial group is filled up with zero bits, without it the leftover
+// bits are dropped and returned as (count, value), so the caller can decide whether
+// they're allowed
+pub fn regroup(
+    input: &[u8],
+    from: u32,
+    to: u32,
+    pad: bool,
+    output: &mut Vec<u8>,
+) -> anyhow::Result<(u32, u32)> {
+    assert!((1..=8).contains(&from) && (1..=8).contains(&to));
+
+    let mask = (1u32 << to) - 1;
+    let mut acc = 0u32;
+    let mut bits = 0u32;
+
+    output.reserve((input.len() * from as usize).div_ceil(to as usize));
+
+    for (i, &value) in input.iter().enumerate() {
+        if (value as u32) >> from != 0 {
+            bail!("the value {value} at offset {i} doesn't fit in {from} bits");
+        }
+
+        acc = acc << from | value as u32;
+        bits += from;
+
+        while bits >= to {
+            bits -= to;
+            output.push((acc >> bits & mask) as u8);
+        }
+
+        // only the bits that haven't been written yet are kept, so it never overflows
+        acc &= (1 << bits) - 1;
+    }
+
+    if pad && bits > 0 {
+        output.push((acc << (to - bits) & mask) as u8);
+        return Ok((0, 0));
+    }
+
+    Ok((bits, acc))
+}
@@ -0,0 +1,135 @@
+use crate::{base64, percent};
+use anyhow::{anyhow, bail};
+
+/*
+* RFC 2397 data URIs, a media type and the data inlined in the URI itself:
+*
+*   data:image/png;base64,iVBORw0KGgo...
+*   data:text/plain;charset=utf-8,hello%20world
+*
+* without a media type it defaults to "text/plain;charset=US-ASCII", and without
+* ";base64" the data is percent-encoded
+*/
+
+const SCHEME: &str = "data:";
+const BASE64: &str = ";base64";
+const DEFAULT_MEDIA_TYPE: &str = "text/plain;charset=US-ASCII";
+const OCTET_STREAM: &str = "application/octet-stream";
+
+// how far into the input to look for an <svg tag, past the xml prolog and comments
+const SNIFF_LEN: usize = 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataUri {
+    pub media_type: String,
+    pub data: Vec<u8>,
+}
+
+impl DataUri {
+    // the media type without its parameters, like "text/plain"
+    pub fn mime(&self) -> &str {
+        self.media_type.split(';').next().unwrap_or_default().trim()
+    }
+}
+
+fn is_text(data: &[u8]) -> bool {
+    std::str::from_utf8(data).is_ok_and(|text| {
+        text.chars()
+            .all(|c| !c.is_control() || matches!(c, '\t' | '\n' | '\r'))
+    })
+}
+
+fn is_svg(data: &[u8]) -> bool {
+    let head = String::from_utf8_lossy(&data[..data.len().min(SNIFF_LEN)]).to_ascii_lowercase();
+    head.trim_start().starts_with('<') && head.contains("<svg")
+}
+
+fn is_json(data: &[u8]) -> bool {
+    let Ok(text) = std::str::from_utf8(data) else {
+        return false;
+    };
+
+    let text = text.trim();
+    (text.starts_with('{') && text.ends_with('}')) || (text.starts_with('[') && text.ends_with(']'))
+}
+
+// guesses the media type from the magic bytes at the start of the data
+pub fn sniff_media_type(data: &[u8]) -> &'static str {
+    match data {
+        [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n', ..] => "image/png",
+        [0xFF, 0xD8, 0xFF, ..] => "image/jpeg",
+        [b'G', b'I', b'F', b'8', b'7' | b'9', b'a', ..] => "image/gif",
+        _ if !is_text(data) => OCTET_STREAM,
+        _ if is_svg(data) => "image/svg+xml",
+        _ if is_json(data) => "application/json",
+        _ => "text/plain;charset=utf-8",
+    }
+}
+
+pub fn encode(data: &[u8]) -> String {
+    encode_with(data, sniff_media_type(data))
+}
+
+pub fn encode_with(data: &[u8], media_type: &str) -> String {
+    format!("{SCHEME}{media_type}{BASE64},{}", base64::encode(data))
+}
+
+pub fn parse(uri: &str) -> anyhow::Result<DataUri> {
+    let uri = uri.trim();
+
+    let rest = uri
+        .get(..SCHEME.len())
+        .filter(|scheme| scheme.eq_ignore_ascii_case(SCHEME))
+        .map(|_| &uri[SCHEME.len()..])
+        .ok_or_else(|| anyhow!("not a data URI, it has to start with {SCHEME:?}"))?;
+
+    let (header, data) = rest
+        .split_once(',')
+        .ok_or_else(|| anyhow!("the data URI is missing the ',' before the data"))?;
+
+    // compared as bytes, the suffix can start inside a multi-byte character
+    let (media_type, is_base64) = match header.len().checked_sub(BASE64.len()) {
+        Some(end) if header.as_bytes()[end..].eq_ignore_ascii_case(BASE64.as_bytes()) => {
+            (&header[..end], true)
+        }
+        _ => (header, false),
+    };
+
+    let media_type = match media_type.trim() {
+        "" => DEFAULT_MEDIA_TYPE.to_string(),
+        // only parameters, like "data:;charset=utf-8,"
+        parameters if parameters.starts_with(';') => format!("text/plain{parameters}"),
+        media_type => {
+            let percent_decoded = percent::decode(media_type)?;
+            String::from_utf8(percent_decoded)?
+        }
+    };
+
+    // even base64 data can have percent-encoded symbols in a URI
+    let mut data = percent::decode(data)?;
+
+    if is_base64 {
+        data.retain(|b| !b.is_ascii_whitespace());
+
+        // padding is often left out of URIs
+        let config = if data.len().is_multiple_of(4) {
+            base64::STANDARD
+        } else {
+            base64::STANDARD_NO_PAD
+        };
+
+        let len = base64::decode_in_place_with(&mut data, &config)
+            .map_err(|e| anyhow!("invalid base64 data: {e}"))?;
+        data.truncate(len);
+    }
+
+    if media_type
+        .split(';')
+        .next()
+        .is_some_and(|mime| !mime.contains('/'))
+    {
+        bail!("invalid media type {media_type:?}");
+    }
+
+    Ok(DataUri { media_type, data })
+}
@@ -0,0 +1,162 @@
+use crate::{alphabet, ascii85, base32, base58, base64, hex};
+
+/*
+* guesses which encoding a blob of text is in
+*
+* every encoding that decodes the input without errors is a candidate, and they're
+* scored by how likely the input is to come out of each one. a symbol drawn from an
+* alphabet of N symbols has a 1/N chance of being any particular one, so an input of
+* n symbols scores -n * ln(N), and the smallest alphabet that explains the input wins:
+*
+*   "deadbeef" decodes as hex, base32, base58, base64 and ascii85, but 8 symbols
+*   that all happen to be hex digits is a lot more likely if they're hex (16^-8)
+*   than if they're base64 (64^-8)
+*
+* the scores are turned into confidences that add up to 1 with a softmax
+*/
+
+// base58 decodes as one big number, which takes time quadratic in the length, and
+// nothing long is written in base58 anyway
+const MAX_BASE58_LEN: usize = 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Base64,
+    Base64Url,
+    Base32,
+    Hex,
+    Base58,
+    Ascii85,
+}
+
+impl Encoding {
+    pub const ALL: [Encoding; 6] = [
+        Encoding::Base64,
+        Encoding::Base64Url,
+        Encoding::Base32,
+        Encoding::Hex,
+        Encoding::Base58,
+        Encoding::Ascii85,
+    ];
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Encoding::Base64 => "base64",
+            Encoding::Base64Url => "base64url",
+            Encoding::Base32 => "base32",
+            Encoding::Hex => "hex",
+            Encoding::Base58 => "base58",
+            Encoding::Ascii85 => "ascii85",
+        }
+    }
+
+    const fn alphabet_len(self) -> usize {
+        match self {
+            Encoding::Base64 | Encoding::Base64Url => 64,
+            Encoding::Base32 => 32,
+            Encoding::Hex => 16,
+            Encoding::Base58 => 58,
+            Encoding::Ascii85 => 85,
+        }
+    }
+
+    // how common the encoding is compared to the others, it matters when the input
+    // can't tell them apart, like base64 without any '+', '/', '-' or '_', or short
+    // base64 that happens to have no '0', 'O', 'I' or 'l' and so is valid base58 too
+    fn prior(self) -> f64 {
+        match self {
+            Encoding::Base64Url => 0.5,
+            Encoding::Base58 => 0.1,
+            _ => 1.0,
+        }
+    }
+
+    // whitespace is ignored, and the padding is optional for the base64s and base32
+    pub fn decode(self, input: &str) -> anyhow::Result<Vec<u8>> {
+        let input: String = input.split_ascii_whitespace().collect();
+
+        match self {
+            Encoding::Base64 => base64::decode_with(&input, &padded(&input, base64::STANDARD)),
+            Encoding::Base64Url => base64::decode_with(&input, &padded(&input, base64::URL_SAFE)),
+            Encoding::Base32 => base32::decode_with(&input, &padded(&input, base32::STANDARD)),
+            Encoding::Hex => hex::decode(&input),
+            Encoding::Base58 => base58::decode(&input),
+            Encoding::Ascii85 => ascii85::decode(&input),
+        }
+    }
+}
+
+// only expect padding if the input is whole blocks
+fn padded<const N: usize>(input: &str, config: alphabet::Config<N>) -> alphabet::Config<N> {
+    config.with_padding(
+        input
+            .len()
+            .is_multiple_of(alphabet::Config::<N>::BLOCK_SYMBOLS),
+    )
+}
+
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub encoding: Encoding,
+    // between 0 and 1, the confidences of all the candidates add up to 1
+    pub confidence: f64,
+    pub decoded: Vec<u8>,
+    // for base58, whether it ends in a valid base58check checksum
+    pub checksum: bool,
+}
+
+// the candidates, best first, or nothing if the input isn't valid in any encoding
+pub fn detect(input: &str) -> Vec<Candidate> {
+    let input: String = input.split_ascii_whitespace().collect();
+    let symbols = input.len();
+    let has_lower = input.bytes().any(|b| b.is_ascii_lowercase());
+    let has_upper = input.bytes().any(|b| b.is_ascii_uppercase());
+
+    let mut scored: Vec<(f64, Candidate)> = Encoding::ALL
+        .into_iter()
+        .filter(|&encoding| encoding != Encoding::Base58 || symbols <= MAX_BASE58_LEN)
+        .filter_map(|encoding| {
+            let decoded = encoding.decode(&input).ok()?;
+
+            let mut alphabet_len = encoding.alphabet_len() as f64;
+
+            // hex and base32 take either case, but an encoder only ever writes one of
+            // them, so mixed case is as unlikely as an alphabet twice the size
+            if matches!(encoding, Encoding::Hex | Encoding::Base32) && has_lower && has_upper {
+                alphabet_len *= 2.0;
+            }
+
+            let mut score = encoding.prior().ln() - symbols as f64 * alphabet_len.ln();
+
+            // a random input passes the 4 byte checksum once in 2^32
+            let checksum = encoding == Encoding::Base58 && base58::decode_check(&input).is_ok();
+            if checksum {
+                score += 32.0 * 2f64.ln();
+            }
+
+            Some((
+                score,
+                Candidate {
+                    encoding,
+                    confidence: 0.0,
+                    decoded,
+                    checksum,
+                },
+            ))
+        })
+        .collect();
+
+    scored.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+
+    // softmax, shifted by the best score so the exponents can't underflow to all zeros
+    let best = scored.first().map_or(0.0, |(score, _)| *score);
+    let total: f64 = scored.iter().map(|(score, _)| (score - best).exp()).sum();
+
+    scored
+        .into_iter()
+        .map(|(score, candidate)| Candidate {
+            confidence: (score - best).exp() / total,
+            ..candidate
+        })
+        .collect()
+}
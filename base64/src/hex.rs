@@ -0,0 +1,221 @@
+use crate::alphabet;
+use anyhow::{anyhow, bail};
+
+/*
+* base16, every byte is two symbols, the high nibble first
+*
+*   0x4f -> 0100 1111 -> "4f"
+*
+* decoding accepts both cases, an optional 0x prefix and whitespace anywhere,
+* so pasted dumps like "DE AD be ef" or "0xdeadbeef\n" just work
+*
+* BASE16 is RFC 4648's strict version, uppercase symbols and nothing else, like basenc --base16
+*/
+
+pub type Alphabet = alphabet::Alphabet<16>;
+
+pub const LOWER_ALPHABET: Alphabet = Alphabet::new(b"0123456789abcdef").case_insensitive();
+pub const UPPER_ALPHABET: Alphabet = Alphabet::new(b"0123456789ABCDEF").case_insensitive();
+pub const BASE16_ALPHABET: Alphabet = Alphabet::new(b"0123456789ABCDEF");
+
+#[derive(Clone, Copy)]
+pub struct Config {
+    pub alphabet: &'static Alphabet,
+    // written between every two bytes, and skipped when decoding
+    pub separator: Option<char>,
+    pub prefix: bool,
+    // no prefix, whitespace or separators when decoding
+    pub strict: bool,
+}
+
+impl Config {
+    pub const fn new(alphabet: &'static Alphabet) -> Self {
+        Config {
+            alphabet,
+            separator: None,
+            prefix: false,
+            strict: false,
+        }
+    }
+
+    pub const fn with_separator(self, separator: char) -> Self {
+        Config {
+            separator: Some(separator),
+            ..self
+        }
+    }
+
+    pub const fn with_prefix(self, prefix: bool) -> Self {
+        Config { prefix, ..self }
+    }
+
+    pub const fn with_strict(self, strict: bool) -> Self {
+        Config { strict, ..self }
+    }
+}
+
+pub const LOWER: Config = Config::new(&LOWER_ALPHABET);
+pub const UPPER: Config = Config::new(&UPPER_ALPHABET);
+pub const BASE16: Config = Config::new(&BASE16_ALPHABET).with_strict(true);
+
+const PREFIX: &str = "0x";
+
+pub fn encode(input: &[u8]) -> String {
+    encode_with(input, &LOWER)
+}
+
+pub fn encode_upper(input: &[u8]) -> String {
+    encode_with(input, &UPPER)
+}
+
+pub fn encode_with(input: &[u8], config: &Config) -> String {
+    let symbols = config.alphabet.symbols();
+    let separator_len = config.separator.map_or(0, char::len_utf8);
+
+    let mut output = String::with_capacity(
+        PREFIX.len() + input.len() * 2 + input.len().saturating_sub(1) * separator_len,
+    );
+
+    if config.prefix {
+        output.push_str(PREFIX);
+    }
+
+    for (i, &b) in input.iter().enumerate() {
+        if let Some(separator) = config.separator.filter(|_| i > 0) {
+            output.push(separator);
+        }
+
+        output.push(symbols[(b >> 4) as usize] as char);
+        output.push(symbols[(b & 0b1111) as usize] as char);
+    }
+
+    output
+}
+
+pub fn decode(input: &str) -> anyhow::Result<Vec<u8>> {
+    decode_with(input, &LOWER)
+}
+
+pub fn decode_with(input: &str, config: &Config) -> anyhow::Result<Vec<u8>> {
+    let (body, offset) = if config.strict {
+        (input, 0)
+    } else {
+        let trimmed = input.trim_start();
+        let offset = input.len() - trimmed.len();
+
+        match trimmed
+            .strip_prefix(PREFIX)
+            .or_else(|| trimmed.strip_prefix("0X"))
+        {
+            Some(body) => (body, offset + PREFIX.len()),
+            None => (trimmed, offset),
+        }
+    };
+
+    let mut output = Vec::with_capacity(body.len() / 2);
+    let mut high = None;
+
+    for (i, c) in body.char_indices() {
+        if !config.strict && (c.is_ascii_whitespace() || Some(c) == config.separator) {
+            continue;
+        }
+
+        let value = u8::try_from(c)
+            .ok()
+            .and_then(|b| config.alphabet.value(b))
+            .ok_or_else(|| anyhow!("invalid hex symbol {c:?} at offset {}", offset + i))?;
+
+        match high.take() {
+            Some(high) => output.push(high << 4 | value),
+            None => high = Some(value),
+        }
+    }
+
+    if high.is_some() {
+        bail!("odd number of hex symbols, the last byte is missing its low nibble");
+    }
+
+    Ok(output)
+}
+
+/*
+* xxd style dumps
+*
+* 00000000: 6865 6c6c 6f20 776f 726c 642c 2074 6869  hello world, thi
+* 00000010: 7320 6973 206d 6f72 6520 7468 616e 2031  s is more than 1
+*/
+
+const DUMP_COLUMNS: usize = 16;
+const DUMP_GROUP: usize = 2;
+// 2 symbols per byte, and a space between every group
+const DUMP_HEX_WIDTH: usize = DUMP_COLUMNS * 2 + DUMP_COLUMNS / DUMP_GROUP - 1;
+
+pub fn dump(input: &[u8]) -> String {
+    let mut output = String::new();
+
+    for (line, chunk) in input.chunks(DUMP_COLUMNS).enumerate() {
+        let hex = chunk
+            .chunks(DUMP_GROUP)
+            .map(encode)
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let text: String = chunk
+            .iter()
+            .map(|&b| {
+                if b.is_ascii_graphic() || b == b' ' {
+                    b as char
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+
+        output.push_str(&format!(
+            "{:08x}: {hex:<DUMP_HEX_WIDTH$}  {text}\n",
+            line * DUMP_COLUMNS
+        ));
+    }
+
+    output
+}
+
+// the most zeros the gaps between lines can add up to, a dump of `xxd -s` starts past 0
+// but an offset far past anything the input could hold is a broken dump
+const MAX_ZERO_FILL: usize = 16 << 20;
+
+// the reverse of `dump`, the offset of every line is respected so gaps are zero filled
+pub fn undump(input: &str) -> anyhow::Result<Vec<u8>> {
+    let mut output = Vec::new();
+
+    for (number, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let (offset, rest) = line
+            .split_once(':')
+            .ok_or_else(|| anyhow!("line {}: missing the offset", number + 1))?;
+
+        let offset = usize::from_str_radix(offset.trim(), 16)
+            .map_err(|e| anyhow!("line {}: invalid offset {offset:?}: {e}", number + 1))?;
+
+        // the text column is separated from the hex by two spaces
+        let rest = rest.strip_prefix(' ').unwrap_or(rest);
+        let hex = rest.split_once("  ").map_or(rest, |(hex, _)| hex);
+
+        let bytes = decode(hex).map_err(|e| anyhow!("line {}: {e}", number + 1))?;
+
+        // every byte in the dump takes at least 2 symbols of the input
+        let end = offset
+            .checked_add(bytes.len())
+            .filter(|&end| end <= input.len() / 2 + MAX_ZERO_FILL)
+            .ok_or_else(|| anyhow!("line {}: the offset {offset:x} is too far", number + 1))?;
+        if output.len() < end {
+            output.resize(end, 0);
+        }
+        output[offset..end].copy_from_slice(&bytes);
+    }
+
+    Ok(output)
+}
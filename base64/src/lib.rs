@@ -0,0 +1,19 @@
+pub mod alphabet;
+pub mod ascii85;
+pub mod base32;
+pub mod base58;
+pub mod base64;
+pub mod bech32;
+pub mod bits;
+pub mod data_uri;
+pub mod detect;
+pub mod hex;
+pub mod pem;
+pub mod percent;
+pub mod quoted_printable;
+#[cfg(feature = "serde")]
+pub mod serde;
+pub mod uu;
+pub mod z85;
+
+mod sha256;
@@ -1,230 +1,832 @@
-use anyhow::{anyhow, Context};
-use clap::Parser;
+use anyhow::{bail, Context};
+use base64::{
+    alphabet::PAD, ascii85, base32, base58, base64 as b64, bech32, data_uri, detect, hex, pem,
+    percent, quoted_printable, uu, z85,
+};
+use clap::{Parser, Subcommand};
 use std::{
-    collections::HashMap,
     fs::File,
-    io::{self, Read},
-    sync::LazyLock,
+    io::{self, Read, Write},
 };
 
-/*
-*Value 	Char 	  	Value 	Char 	  	Value 	Char 	  	Value 	Char
-0 	A 	  	16 	Q 	  	32 	g 	  	48 	w
-1 	B 	  	17 	R 	  	33 	h 	  	49 	x
-2 	C 	  	18 	S 	  	34 	i 	  	50 	y
-3 	D 	  	19 	T 	  	35 	j 	  	51 	z
-4 	E 	  	20 	U 	  	36 	k 	  	52 	0
-5 	F 	  	21 	V 	  	37 	l 	  	53 	1
-6 	G 	  	22 	W 	  	38 	m 	  	54 	2
-7 	H 	  	23 	X 	  	39 	n 	  	55 	3
-8 	I 	  	24 	Y 	  	40 	o 	  	56 	4
-9 	J 	  	25 	Z 	  	41 	p 	  	57 	5
-10 	K 	  	26 	a 	  	42 	q 	  	58 	6
-11 	L 	  	27 	b 	  	43 	r 	  	59 	7
-12 	M 	  	28 	c 	  	44 	s 	  	60 	8
-13 	N 	  	29 	d 	  	45 	t 	  	61 	9
-14 	O 	  	30 	e 	  	46 	u 	  	62 	+
-15 	P 	  	31 	f 	  	47 	v 	  	63 	/
-*
-*/
-
-#[rustfmt::skip]
-static TABLE: LazyLock<HashMap<u8, char>> = LazyLock::new(|| {
-    let table = [
-        (0, 'A'), (1, 'B'), (2, 'C'), (3, 'D'),
-        (4, 'E'), (5, 'F'), (6, 'G'), (7, 'H'),
-        (8, 'I'), (9, 'J'), (10, 'K'), (11, 'L'),
-        (12, 'M'), (13, 'N'), (14, 'O'), (15, 'P'),
-        (16, 'Q'), (17, 'R'), (18, 'S'), (19, 'T'),
-        (20, 'U'), (21, 'V'), (22, 'W'), (23, 'X'),
-        (24, 'Y'), (25, 'Z'), (26, 'a'), (27, 'b'),
-        (28, 'c'), (29, 'd'), (30, 'e'), (31, 'f'),
-        (32, 'g'), (33, 'h'), (34, 'i'), (35, 'j'),
-        (36, 'k'), (37, 'l'), (38, 'm'), (39, 'n'),
-        (40, 'o'), (41, 'p'), (42, 'q'), (43, 'r'),
-        (44, 's'), (45, 't'), (46, 'u'), (47, 'v'),
-        (48, 'w'), (49, 'x'), (50, 'y'), (51, 'z'),
-        (52, '0'), (53, '1'), (54, '2'), (55, '3'),
-        (56, '4'), (57, '5'), (58, '6'), (59, '7'),
-        (60, '8'), (61, '9'), (62, '+'), (63, '/'),
-    ];
-
-    table.into_iter().collect()
-});
-
 #[derive(Parser)]
-#[command(author, version, about, long_about = None)]
+#[command(author, version, about, long_about = None, args_conflicts_with_subcommands = true)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     #[arg(short, long)]
     decode: bool,
+    /// when decoding, ignore anything that isn't part of the alphabet
+    #[arg(short, long)]
+    ignore_garbage: bool,
+    /// wrap encoded lines after COLS characters, 0 disables wrapping
+    #[arg(short, long, value_name = "COLS", default_value_t = 76)]
+    wrap: usize,
+    /// file and url safe base64
+    #[arg(long, group = "encoding")]
+    base64url: bool,
+    /// base32, like the base32 subcommand
+    #[arg(long, group = "encoding")]
+    base32: bool,
+    /// uppercase hex
+    #[arg(long, group = "encoding")]
+    base16: bool,
+    /// where to write the output, defaults to stdout
+    #[arg(short, long)]
+    output: Option<String>,
     input: Option<String>,
 }
 
-fn get_table_value(index: u8) -> anyhow::Result<char> {
-    TABLE
-        .get(&index)
-        .copied()
-        .ok_or(anyhow!("the given base64 index is invalid"))
+// the encodings the top level command takes, with coreutils' basenc flags
+#[derive(Clone, Copy)]
+enum Basenc {
+    Base64,
+    Base64Url,
+    Base32,
+    Base16,
 }
 
-fn get_table_index(value: char) -> Option<u8> {
-    TABLE
-        .iter()
-        .find(|(_, &map_value)| value == map_value)
-        .map(|(i, _)| *i)
+impl Basenc {
+    fn encode(self, input: &[u8]) -> String {
+        match self {
+            Basenc::Base64 => b64::encode(input),
+            Basenc::Base64Url => b64::encode_with(input, &b64::URL_SAFE),
+            Basenc::Base32 => base32::encode(input),
+            Basenc::Base16 => hex::encode_upper(input),
+        }
+    }
+
+    fn decode(self, input: &str) -> anyhow::Result<Vec<u8>> {
+        match self {
+            Basenc::Base64 => b64::decode(input),
+            Basenc::Base64Url => b64::decode_with(input, &b64::URL_SAFE),
+            Basenc::Base32 => base32::decode(input),
+            Basenc::Base16 => hex::decode_with(input, &hex::BASE16),
+        }
+    }
+
+    fn is_symbol(self, symbol: u8) -> bool {
+        match self {
+            Basenc::Base64 => b64::STANDARD_ALPHABET.value(symbol).is_some() || symbol == PAD,
+            Basenc::Base64Url => b64::URL_SAFE_ALPHABET.value(symbol).is_some() || symbol == PAD,
+            Basenc::Base32 => base32::STANDARD_ALPHABET.value(symbol).is_some() || symbol == PAD,
+            Basenc::Base16 => hex::BASE16_ALPHABET.value(symbol).is_some(),
+        }
+    }
 }
 
-#[inline]
-fn get_first_chunk(byte: u8) -> u8 {
-    (byte >> 2) & 0b111111
+#[derive(Subcommand)]
+enum Command {
+    /// wrap or unwrap PEM armored data
+    Pem {
+        #[command(subcommand)]
+        action: PemAction,
+    },
+    /// encode or decode base32
+    Base32(Base32Args),
+    /// encode or decode hex, or make an xxd style dump
+    Hex(HexArgs),
+    /// encode or decode base58, optionally with the base58check checksum
+    Base58(Base58Args),
+    /// encode or decode bech32 and bech32m (BIP-173, BIP-350)
+    Bech32(Bech32Args),
+    /// encode or decode ascii85 (adobe by default)
+    Ascii85(Ascii85Args),
+    /// encode or decode ZeroMQ's z85
+    Z85(Z85Args),
+    /// uuencode or uudecode a file (or xxencode with --xx)
+    Uu(UuArgs),
+    /// encode or decode quoted-printable
+    Qp(QpArgs),
+    /// percent-encode or decode for urls
+    Url(UrlArgs),
+    /// turn a file into a data: URI, or a data: URI back into bytes
+    #[command(name = "datauri")]
+    DataUri(DataUriArgs),
+    /// guess which encoding the input is in
+    Detect(DetectArgs),
 }
 
-#[inline]
-fn get_second_chunk(byte1: u8, byte2: u8) -> u8 {
-    (byte1 << 4 | byte2 >> 4) & 0b111111
+#[derive(clap::Args)]
+struct Base32Args {
+    #[arg(short, long)]
+    decode: bool,
+    /// use the extended hex alphabet
+    #[arg(long, conflicts_with = "crockford")]
+    hex: bool,
+    /// use Crockford's alphabet
+    #[arg(long)]
+    crockford: bool,
+    /// append (or verify) Crockford's check symbol
+    #[arg(long, requires = "crockford")]
+    check: bool,
+    /// don't write (or expect) the trailing '=' padding
+    #[arg(long)]
+    no_pad: bool,
+    input: Option<String>,
 }
 
-#[inline]
-fn get_third_chunk(byte2: u8, byte3: u8) -> u8 {
-    (((byte2 & 0b1111) << 2) | byte3 >> 6) & 0b111111
+#[derive(clap::Args)]
+struct HexArgs {
+    #[arg(short, long)]
+    decode: bool,
+    #[arg(short, long)]
+    upper: bool,
+    /// written between the bytes, and skipped when decoding
+    #[arg(short, long)]
+    separator: Option<char>,
+    /// start the output with 0x
+    #[arg(short, long)]
+    prefix: bool,
+    /// print an xxd style dump
+    #[arg(long, conflicts_with_all = ["decode", "reverse"])]
+    dump: bool,
+    /// turn an xxd style dump back into bytes
+    #[arg(short, long, conflicts_with = "decode")]
+    reverse: bool,
+    input: Option<String>,
 }
 
-#[inline]
-fn get_fourth_chunk(byte3: u8) -> u8 {
-    byte3 & 0b111111
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum Base58Alphabet {
+    Bitcoin,
+    Ripple,
+    Flickr,
 }
 
-fn encode(input: String) -> anyhow::Result<String> {
-    let mut output = String::with_capacity(input.len() * 3);
+#[derive(clap::Args)]
+struct Base58Args {
+    #[arg(short, long)]
+    decode: bool,
+    /// append (or verify and strip) the 4 byte double sha256 checksum
+    #[arg(short, long)]
+    check: bool,
+    #[arg(short, long, value_enum, default_value_t = Base58Alphabet::Bitcoin)]
+    alphabet: Base58Alphabet,
+    input: Option<String>,
+}
 
-    let mut input = input.as_bytes().iter();
+#[derive(clap::Args)]
+struct Bech32Args {
+    #[arg(short, long)]
+    decode: bool,
+    /// the human readable part, like "bc", when decoding it has to match
+    #[arg(long, required_unless_present = "decode")]
+    hrp: Option<String>,
+    /// use the bech32m checksum, decoding accepts both
+    #[arg(short = 'm', long)]
+    bech32m: bool,
+    /// a segwit address, the first byte is the witness version and the rest the program,
+    /// the checksum follows from the version
+    #[arg(short, long, conflicts_with = "bech32m")]
+    segwit: bool,
+    input: Option<String>,
+}
 
-    while let (Some(b1), b2, b3) = (input.next(), input.next(), input.next()) {
-        match (b1, b2, b3) {
-            (b1, Some(b2), Some(b3)) => {
-                /*
-                    011100 10
-                    0110 0101
-                    01 110011
+#[derive(clap::Args)]
+struct Ascii85Args {
+    #[arg(short, long)]
+    decode: bool,
+    /// leave out the <~ ~> delimiters and write four spaces as 'y' (btoa style)
+    #[arg(long)]
+    no_delimiters: bool,
+    input: Option<String>,
+}
 
-                    011100
-                    100110
-                    010101
-                    110011
-                */
+#[derive(clap::Args)]
+struct Z85Args {
+    #[arg(short, long)]
+    decode: bool,
+    input: Option<String>,
+}
 
-                let first_part = get_first_chunk(*b1);
-                let second_part = get_second_chunk(*b1, *b2);
-                let third_part = get_third_chunk(*b2, *b3);
-                let fourth_part = get_fourth_chunk(*b3);
+#[derive(clap::Args)]
+struct UuArgs {
+    #[arg(short, long)]
+    decode: bool,
+    /// use xxencode's alphabet
+    #[arg(long)]
+    xx: bool,
+    /// write zeros as '`' instead of ' '
+    #[arg(long, conflicts_with = "xx")]
+    backtick: bool,
+    /// the file name in the begin line, defaults to the input's
+    #[arg(long)]
+    name: Option<String>,
+    /// the octal file mode in the begin line, defaults to the input's
+    #[arg(long, value_parser = parse_mode)]
+    mode: Option<u32>,
+    /// where to write the output, when decoding it defaults to the name in the begin line,
+    /// use - for stdout
+    #[arg(short, long)]
+    output: Option<String>,
+    input: Option<String>,
+}
 
-                output.push(get_table_value(first_part)?);
+#[derive(clap::Args)]
+struct QpArgs {
+    #[arg(short, long)]
+    decode: bool,
+    /// encode the line breaks too, instead of keeping them as line breaks
+    #[arg(short, long)]
+    binary: bool,
+    input: Option<String>,
+}
 
-                output.push(get_table_value(second_part)?);
-                output.push(get_table_value(third_part)?);
-                output.push(get_table_value(fourth_part)?);
-            }
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum UrlPart {
+    Component,
+    Path,
+    Query,
+}
 
-            (b1, Some(b2), None) => {
-                let first_part = get_first_chunk(*b1);
-                let second_part = get_second_chunk(*b1, *b2);
-                let third_part = ((b2 & 0b1111) << 2) & 0b111111;
+#[derive(clap::Args)]
+struct UrlArgs {
+    #[arg(short, long)]
+    decode: bool,
+    /// which reserved characters are left alone, a component encodes all of them
+    #[arg(short, long, value_enum, default_value_t = UrlPart::Component)]
+    part: UrlPart,
+    /// extra characters to leave alone
+    #[arg(short, long, default_value = "", value_parser = parse_ascii)]
+    keep: String,
+    /// application/x-www-form-urlencoded, spaces are written as '+'
+    #[arg(short, long)]
+    form: bool,
+    input: Option<String>,
+}
 
-                output.push(get_table_value(first_part)?);
-                output.push(get_table_value(second_part)?);
-                output.push(get_table_value(third_part)?);
-                output.push('=');
-            }
-            (b1, None, None) => {
-                let first_part = get_first_chunk(*b1);
-                let second_part = (b1 << 4) & 0b111111;
+#[derive(clap::Args)]
+struct DataUriArgs {
+    #[arg(short, long)]
+    decode: bool,
+    /// the media type to use instead of guessing it from the contents
+    #[arg(short, long, conflicts_with = "decode")]
+    media_type: Option<String>,
+    /// print the media type of the URI instead of its data
+    #[arg(long, requires = "decode")]
+    print_type: bool,
+    /// where to write the output, defaults to stdout
+    #[arg(short, long)]
+    output: Option<String>,
+    input: Option<String>,
+}
 
-                output.push(get_table_value(first_part)?);
-                output.push(get_table_value(second_part)?);
-                output.push_str("==");
-            }
+#[derive(clap::Args)]
+struct DetectArgs {
+    /// decode the input with the most likely encoding
+    #[arg(short, long)]
+    decode: bool,
+    /// where to write the decoded data, defaults to stdout
+    #[arg(short, long, requires = "decode")]
+    output: Option<String>,
+    input: Option<String>,
+}
+
+fn parse_ascii(value: &str) -> Result<String, String> {
+    if value.is_ascii() {
+        Ok(value.to_string())
+    } else {
+        Err(format!("{value:?} isn't ascii"))
+    }
+}
+
+fn parse_mode(mode: &str) -> Result<u32, String> {
+    u32::from_str_radix(mode, 8).map_err(|e| format!("invalid octal mode {mode:?}: {e}"))
+}
+
+#[derive(Subcommand)]
+enum PemAction {
+    /// wrap the input in a PEM block
+    Encode {
+        #[arg(short, long, default_value = "DATA")]
+        label: String,
+        input: Option<String>,
+    },
+    /// decode every PEM block in the input and write their contents
+    Decode {
+        /// only decode the blocks with this label
+        #[arg(short, long)]
+        label: Option<String>,
+        /// list the blocks instead of writing their contents
+        #[arg(long)]
+        list: bool,
+        input: Option<String>,
+    },
+}
+
+fn read_input(input: Option<&str>) -> anyhow::Result<Vec<u8>> {
+    let mut buffer = Vec::new();
 
-            (_, None, Some(_)) => unreachable!(),
+    if let Some(file) = input {
+        if file == "-" {
+            io::stdin().read_to_end(&mut buffer)?;
+        } else {
+            File::open(file)
+                .with_context(|| format!("Failed to open file: {}", file))?
+                .read_to_end(&mut buffer)?;
+        }
+    } else {
+        io::stdin()
+            .read_to_end(&mut buffer)
+            .with_context(|| "Failed to read from the stdio".to_string())?;
+    }
+    Ok(buffer)
+}
+
+fn read_input_string(input: Option<&str>) -> anyhow::Result<String> {
+    String::from_utf8(read_input(input)?).context("the input is not valid utf-8")
+}
+
+fn run_pem(action: PemAction) -> anyhow::Result<()> {
+    match action {
+        PemAction::Encode { label, input } => {
+            let contents = read_input(input.as_deref())?;
+            print!("{}", pem::encode(&pem::Pem::new(label, contents)));
+        }
+        PemAction::Decode { label, list, input } => {
+            let input = read_input_string(input.as_deref())?;
+            let pems = pem::parse(&input)?
+                .into_iter()
+                .filter(|p| label.as_ref().is_none_or(|label| &p.label == label));
+
+            let mut stdout = io::stdout().lock();
+
+            for p in pems {
+                if list {
+                    writeln!(stdout, "{}: {} bytes", p.label, p.contents.len())?;
+                    for (key, value) in &p.headers {
+                        writeln!(stdout, "    {key}: {value}")?;
+                    }
+                } else {
+                    stdout.write_all(&p.contents)?;
+                }
+            }
         }
     }
 
-    Ok(output)
+    Ok(())
 }
 
-fn decode(input: String) -> anyhow::Result<String> {
-    let mut buffer = Vec::with_capacity(input.len() * 3 / 4);
-    let mut input = input.chars();
+fn run_base32(args: Base32Args) -> anyhow::Result<()> {
+    let config = if args.hex {
+        base32::HEX
+    } else {
+        base32::STANDARD
+    }
+    .with_padding(!args.no_pad);
+
+    let input = read_input(args.input.as_deref())?;
+
+    if !args.decode {
+        let output = if args.crockford {
+            base32::encode_crockford(&input, args.check)
+        } else {
+            base32::encode_with(&input, &config)
+        };
+
+        println!("{output}");
+    } else {
+        let input: String = String::from_utf8(input)
+            .context("the input is not valid utf-8")?
+            .split_ascii_whitespace()
+            .collect();
 
-    while let (Some(c1), Some(c2), Some(c3), Some(c4)) =
-        (input.next(), input.next(), input.next(), input.next())
-    {
-        /*
-            01110010
-            01100101
-            01110011
+        let output = if args.crockford {
+            base32::decode_crockford(&input, args.check)?
+        } else {
+            base32::decode_with(&input, &config)?
+        };
 
-            011100
-            10 0110
-            0101 01
-            110011
-        */
+        io::stdout().lock().write_all(&output)?;
+    }
 
-        let not_found_error = |c: char| anyhow!("Failed to get the index by the value, value: {c}");
+    Ok(())
+}
 
-        let first_part_byte = get_table_index(c1).ok_or_else(|| not_found_error(c1))?;
-        let second_part_byte = get_table_index(c2).ok_or_else(|| not_found_error(c2))?;
-        let third_part_byte = get_table_index(c3);
-        let fourth_part_byte = get_table_index(c4);
+fn run_hex(args: HexArgs) -> anyhow::Result<()> {
+    let mut config = if args.upper { hex::UPPER } else { hex::LOWER }.with_prefix(args.prefix);
+    if let Some(separator) = args.separator {
+        config = config.with_separator(separator);
+    }
 
-        let first_letter = first_part_byte << 2 | second_part_byte >> 4;
-        buffer.push(first_letter);
+    let input = read_input(args.input.as_deref())?;
 
-        if third_part_byte.is_some() {
-            let third_part_byte = third_part_byte.unwrap();
+    if args.dump {
+        print!("{}", hex::dump(&input));
+    } else if args.decode || args.reverse {
+        let input = String::from_utf8(input).context("the input is not valid utf-8")?;
 
-            let second_letter = ((second_part_byte & 0b1111) << 4) | third_part_byte >> 2;
-            buffer.push(second_letter);
+        let output = if args.reverse {
+            hex::undump(&input)?
+        } else {
+            hex::decode_with(&input, &config)?
+        };
 
-            if fourth_part_byte.is_some() {
-                let fourth_part_byte = fourth_part_byte.unwrap();
+        io::stdout().lock().write_all(&output)?;
+    } else {
+        println!("{}", hex::encode_with(&input, &config));
+    }
+
+    Ok(())
+}
+
+fn run_base58(args: Base58Args) -> anyhow::Result<()> {
+    let alphabet = match args.alphabet {
+        Base58Alphabet::Bitcoin => &base58::BITCOIN_ALPHABET,
+        Base58Alphabet::Ripple => &base58::RIPPLE_ALPHABET,
+        Base58Alphabet::Flickr => &base58::FLICKR_ALPHABET,
+    };
+
+    let input = read_input(args.input.as_deref())?;
+
+    if !args.decode {
+        let output = if args.check {
+            base58::encode_check_with(&input, alphabet)
+        } else {
+            base58::encode_with(&input, alphabet)
+        };
+
+        println!("{output}");
+    } else {
+        let input = String::from_utf8(input).context("the input is not valid utf-8")?;
+        let input = input.trim();
+
+        let output = if args.check {
+            base58::decode_check_with(input, alphabet)?
+        } else {
+            base58::decode_with(input, alphabet)?
+        };
+
+        io::stdout().lock().write_all(&output)?;
+    }
 
-                let third_letter = (third_part_byte & 0b11) << 6 | fourth_part_byte;
-                buffer.push(third_letter);
+    Ok(())
+}
+
+fn run_bech32(args: Bech32Args) -> anyhow::Result<()> {
+    if !args.decode {
+        let hrp = args.hrp.as_deref().unwrap_or_default();
+        let variant = if args.bech32m {
+            bech32::Variant::Bech32m
+        } else {
+            bech32::Variant::Bech32
+        };
+
+        let input = read_input(args.input.as_deref())?;
+        let encoded = match input.split_first() {
+            Some((&version, program)) if args.segwit => {
+                let witness = bech32::Witness {
+                    version,
+                    program: program.to_vec(),
+                };
+                bech32::encode_segwit(hrp, &witness)?
             }
+            None if args.segwit => bail!("a segwit address needs at least the witness version"),
+            _ => bech32::encode(hrp, &input, variant)?,
+        };
+
+        println!("{encoded}");
+    } else {
+        let input = read_input_string(args.input.as_deref())?;
+        let decoded = bech32::decode(input.trim())?;
+
+        if let Some(hrp) = args
+            .hrp
+            .filter(|hrp| !hrp.eq_ignore_ascii_case(&decoded.hrp))
+        {
+            bail!(
+                "expected the human readable part {hrp:?}, got {:?}",
+                decoded.hrp
+            );
         }
+
+        let output = if args.segwit {
+            let witness = decoded.witness()?;
+            [&[witness.version], &witness.program[..]].concat()
+        } else {
+            match decoded.bytes() {
+                Err(e) if decoded.witness().is_ok() => {
+                    bail!("{e}, it looks like a segwit address, decode it with --segwit")
+                }
+                bytes => bytes?,
+            }
+        };
+
+        io::stdout().lock().write_all(&output)?;
     }
 
-    Ok(String::from_utf8(buffer)?)
+    Ok(())
 }
 
-fn read_input(input: Option<&str>) -> anyhow::Result<String> {
-    let mut buffer = String::new();
+fn run_ascii85(args: Ascii85Args) -> anyhow::Result<()> {
+    let config = if args.no_delimiters {
+        ascii85::BTOA
+    } else {
+        ascii85::ADOBE
+    };
 
-    if let Some(file) = input {
-        if file == "-" {
-            io::stdin().read_to_string(&mut buffer)?;
-        } else {
-            File::open(file)
-                .with_context(|| format!("Failed to open file: {}", file))?
-                .read_to_string(&mut buffer)?;
+    if !args.decode {
+        let input = read_input(args.input.as_deref())?;
+        println!("{}", ascii85::encode_with(&input, &config));
+    } else {
+        let input = read_input_string(args.input.as_deref())?;
+        io::stdout().lock().write_all(&ascii85::decode(&input)?)?;
+    }
+
+    Ok(())
+}
+
+fn run_z85(args: Z85Args) -> anyhow::Result<()> {
+    if !args.decode {
+        let input = read_input(args.input.as_deref())?;
+        println!("{}", z85::encode(&input)?);
+    } else {
+        let input = read_input_string(args.input.as_deref())?;
+        io::stdout().lock().write_all(&z85::decode(input.trim())?)?;
+    }
+
+    Ok(())
+}
+
+fn write_output(output: Option<&str>, contents: &[u8]) -> anyhow::Result<()> {
+    match output {
+        Some(file) if file != "-" => {
+            std::fs::write(file, contents).with_context(|| format!("Failed to write file: {file}"))
         }
+        _ => Ok(io::stdout().lock().write_all(contents)?),
+    }
+}
+
+#[cfg(unix)]
+fn file_mode(file: &str) -> Option<u32> {
+    use std::os::unix::fs::PermissionsExt;
+
+    Some(std::fs::metadata(file).ok()?.permissions().mode() & 0o777)
+}
+
+#[cfg(not(unix))]
+fn file_mode(_file: &str) -> Option<u32> {
+    None
+}
+
+#[cfg(unix)]
+fn set_file_mode(file: &str, mode: u32) -> anyhow::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    std::fs::set_permissions(file, std::fs::Permissions::from_mode(mode & 0o777))
+        .with_context(|| format!("Failed to set the mode of {file}"))
+}
+
+#[cfg(not(unix))]
+fn set_file_mode(_file: &str, _mode: u32) -> anyhow::Result<()> {
+    Ok(())
+}
+
+fn run_uu(args: UuArgs) -> anyhow::Result<()> {
+    let alphabet = match (args.xx, args.backtick) {
+        (true, _) => &uu::XX_ALPHABET,
+        (false, true) => &uu::UU_BACKTICK_ALPHABET,
+        (false, false) => &uu::UU_ALPHABET,
+    };
+
+    let file_name = args.input.as_deref().filter(|&file| file != "-");
+
+    if !args.decode {
+        let contents = read_input(args.input.as_deref())?;
+
+        let name = args
+            .name
+            .or_else(|| {
+                let path = std::path::Path::new(file_name?);
+                Some(path.file_name()?.to_string_lossy().into_owned())
+            })
+            .unwrap_or_else(|| "-".to_string());
+
+        let mut file = uu::UuFile::new(name, contents);
+        if let Some(mode) = args.mode.or_else(|| file_mode(file_name?)) {
+            file.mode = mode;
+        }
+
+        write_output(
+            args.output.as_deref(),
+            uu::encode_with(&file, alphabet).as_bytes(),
+        )
     } else {
-        io::stdin()
-            .read_to_string(&mut buffer)
-            .with_context(|| "Failed to read from the stdio".to_string())?;
+        let input = read_input_string(args.input.as_deref())?;
+        let file = uu::decode_with(&input, alphabet)?;
+
+        // only the last component of the name in the begin line is used, so a crafted
+        // file can't write outside of the current directory
+        let output = match args.output {
+            Some(output) => output,
+            None => std::path::Path::new(&file.name)
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .filter(|name| name != "-")
+                .unwrap_or_else(|| "-".to_string()),
+        };
+
+        write_output(Some(&output), &file.contents)?;
+
+        if output != "-" && output != "/dev/stdout" {
+            set_file_mode(&output, file.mode)?;
+        }
+
+        Ok(())
     }
-    Ok(buffer)
 }
-fn main() -> anyhow::Result<()> {
-    let args = Args::parse();
+
+fn run_qp(args: QpArgs) -> anyhow::Result<()> {
+    let config = if args.binary {
+        quoted_printable::BINARY
+    } else {
+        quoted_printable::TEXT
+    };
 
     if !args.decode {
         let input = read_input(args.input.as_deref())?;
-        println!("{}", encode(input)?);
+        print!("{}", quoted_printable::encode_with(&input, &config));
     } else {
+        let input = read_input_string(args.input.as_deref())?;
+        io::stdout()
+            .lock()
+            .write_all(&quoted_printable::decode(&input)?)?;
+    }
+
+    Ok(())
+}
+
+fn run_url(args: UrlArgs) -> anyhow::Result<()> {
+    let config = match args.part {
+        UrlPart::Component => percent::COMPONENT,
+        UrlPart::Path => percent::PATH,
+        UrlPart::Query => percent::QUERY,
+    }
+    .keep(args.keep.as_bytes())
+    .with_form(args.form);
+
+    if !args.decode {
         let input = read_input(args.input.as_deref())?;
-        println!("{}", decode(input)?)
+        println!("{}", percent::encode_with(&input, &config));
+    } else {
+        let input = read_input_string(args.input.as_deref())?;
+        io::stdout()
+            .lock()
+            .write_all(&percent::decode_with(input.trim_end(), &config)?)?;
+    }
+
+    Ok(())
+}
+
+fn run_data_uri(args: DataUriArgs) -> anyhow::Result<()> {
+    if !args.decode {
+        let input = read_input(args.input.as_deref())?;
+
+        let mut uri = match args.media_type {
+            Some(media_type) => data_uri::encode_with(&input, &media_type),
+            None => data_uri::encode(&input),
+        };
+        uri.push('\n');
+
+        write_output(args.output.as_deref(), uri.as_bytes())
+    } else {
+        let input = read_input_string(args.input.as_deref())?;
+        let uri = data_uri::parse(&input)?;
+
+        if args.print_type {
+            let media_type = format!("{}\n", uri.media_type);
+            write_output(args.output.as_deref(), media_type.as_bytes())
+        } else {
+            write_output(args.output.as_deref(), &uri.data)
+        }
+    }
+}
+
+fn run_detect(args: DetectArgs) -> anyhow::Result<()> {
+    let input = read_input_string(args.input.as_deref())?;
+    let candidates = detect::detect(&input);
+
+    let Some(best) = candidates.first() else {
+        bail!("the input isn't valid in any of the encodings");
+    };
+
+    if args.decode {
+        return write_output(args.output.as_deref(), &best.decoded);
+    }
+
+    for candidate in &candidates {
+        println!(
+            "{:<10} {:>6.2}%  {} bytes{}",
+            candidate.encoding.name(),
+            candidate.confidence * 100.0,
+            candidate.decoded.len(),
+            if candidate.checksum {
+                ", valid base58check checksum"
+            } else {
+                ""
+            }
+        );
     }
 
     Ok(())
 }
+
+// breaks the encoded output into lines of `width`, with a newline after the last one
+// too, a width of 0 leaves it as one line without a newline (like coreutils)
+fn wrap(encoded: &str, width: usize) -> String {
+    if width == 0 {
+        return encoded.to_string();
+    }
+
+    let mut output = String::with_capacity(encoded.len() + encoded.len() / width + 1);
+    let mut rest = encoded;
+
+    while !rest.is_empty() {
+        let (line, next) = rest.split_at(rest.len().min(width));
+        output.push_str(line);
+        output.push('\n');
+        rest = next;
+    }
+
+    output
+}
+
+// coreutils decodes padded inputs that were concatenated, "aGk=aGk=" is "hihi", so
+// the input is split after every run of padding
+fn padded_chunks(input: &str) -> impl Iterator<Item = &str> {
+    let mut rest = input;
+
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+
+        let end = match rest.find(PAD as char) {
+            Some(pad) => rest[pad..]
+                .find(|c| c != PAD as char)
+                .map_or(rest.len(), |len| pad + len),
+            None => rest.len(),
+        };
+
+        let (chunk, next) = rest.split_at(end);
+        rest = next;
+        Some(chunk)
+    })
+}
+
+fn run_basenc(args: Args) -> anyhow::Result<()> {
+    let encoding = if args.base64url {
+        Basenc::Base64Url
+    } else if args.base32 {
+        Basenc::Base32
+    } else if args.base16 {
+        Basenc::Base16
+    } else {
+        Basenc::Base64
+    };
+
+    let mut input = read_input(args.input.as_deref())?;
+
+    if !args.decode {
+        let encoded = wrap(&encoding.encode(&input), args.wrap);
+        return write_output(args.output.as_deref(), encoded.as_bytes());
+    }
+
+    // only line breaks are skipped, anything else has to be ignored explicitly
+    if args.ignore_garbage {
+        input.retain(|&b| encoding.is_symbol(b));
+    } else {
+        input.retain(|&b| b != b'\n');
+    }
+
+    let input = String::from_utf8(input).context("the input is not valid utf-8")?;
+
+    let mut output = Vec::with_capacity(input.len());
+    for chunk in padded_chunks(&input) {
+        output.extend(encoding.decode(chunk)?);
+    }
+
+    write_output(args.output.as_deref(), &output)
+}
+
+fn main() -> anyhow::Result<()> {
+    let mut args = Args::parse();
+
+    match args.command.take() {
+        Some(Command::Pem { action }) => run_pem(action),
+        Some(Command::Base32(args)) => run_base32(args),
+        Some(Command::Hex(args)) => run_hex(args),
+        Some(Command::Base58(args)) => run_base58(args),
+        Some(Command::Bech32(args)) => run_bech32(args),
+        Some(Command::Ascii85(args)) => run_ascii85(args),
+        Some(Command::Z85(args)) => run_z85(args),
+        Some(Command::Uu(args)) => run_uu(args),
+        Some(Command::Qp(args)) => run_qp(args),
+        Some(Command::Url(args)) => run_url(args),
+        Some(Command::DataUri(args)) => run_data_uri(args),
+        Some(Command::Detect(args)) => run_detect(args),
+        None => run_basenc(args),
+    }
+}
@@ -0,0 +1,149 @@
+use crate::hex;
+use anyhow::anyhow;
+
+/*
+* RFC 3986 percent-encoding, every byte outside of the unreserved set is written as
+* "%XX" in uppercase hex:
+*
+*   "a b/c?d=é" -> "a%20b%2Fc%3Fd%3D%C3%A9"
+*
+* which of the reserved characters ("/", "?", "=", ...) are left alone depends on
+* where the result goes, so that's configurable. form encoding
+* (application/x-www-form-urlencoded) also writes spaces as '+'.
+*/
+
+const ESCAPE: u8 = b'%';
+
+// a set of ascii bytes as a bitmask, so it can be built in a const
+#[derive(Clone, Copy)]
+pub struct AsciiSet(u128);
+
+impl AsciiSet {
+    pub const EMPTY: AsciiSet = AsciiSet(0);
+
+    pub const fn add(self, bytes: &[u8]) -> Self {
+        let mut mask = self.0;
+
+        let mut i = 0;
+        while i < bytes.len() {
+            assert!(bytes[i].is_ascii(), "the set can only hold ascii");
+            mask |= 1 << bytes[i];
+            i += 1;
+        }
+
+        AsciiSet(mask)
+    }
+
+    pub const fn contains(&self, b: u8) -> bool {
+        b.is_ascii() && self.0 & (1 << b) != 0
+    }
+}
+
+const UNRESERVED: AsciiSet = AsciiSet::EMPTY
+    .add(b"ABCDEFGHIJKLMNOPQRSTUVWXYZ")
+    .add(b"abcdefghijklmnopqrstuvwxyz")
+    .add(b"0123456789-._~");
+
+pub const GEN_DELIMS: &[u8] = b":/?#[]@";
+pub const SUB_DELIMS: &[u8] = b"!$&'()*+,;=";
+
+#[derive(Clone, Copy)]
+pub struct Config {
+    // the bytes that are left as is, on top of the unreserved ones
+    pub keep: AsciiSet,
+    pub form: bool,
+}
+
+impl Config {
+    pub const fn new() -> Self {
+        Config {
+            keep: AsciiSet::EMPTY,
+            form: false,
+        }
+    }
+
+    pub const fn keep(self, bytes: &[u8]) -> Self {
+        Config {
+            keep: self.keep.add(bytes),
+            ..self
+        }
+    }
+
+    pub const fn with_form(self, form: bool) -> Self {
+        Config { form, ..self }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// a single path segment, query key or value, everything reserved is encoded
+pub const COMPONENT: Config = Config::new();
+// a whole path, the segments are kept apart by the '/'
+pub const PATH: Config = Config::new().keep(SUB_DELIMS).keep(b":@/");
+// a whole query string or fragment
+pub const QUERY: Config = Config::new().keep(SUB_DELIMS).keep(b":@/?");
+pub const FORM: Config = Config::new().with_form(true);
+
+pub fn encode(input: &[u8]) -> String {
+    encode_with(input, &COMPONENT)
+}
+
+pub fn encode_with(input: &[u8], config: &Config) -> String {
+    let symbols = hex::UPPER_ALPHABET.symbols();
+    let mut output = String::with_capacity(input.len());
+
+    for &b in input {
+        if UNRESERVED.contains(b) || config.keep.contains(b) {
+            output.push(b as char);
+        } else if config.form && b == b' ' {
+            output.push('+');
+        } else {
+            output.push(ESCAPE as char);
+            output.push(symbols[(b >> 4) as usize] as char);
+            output.push(symbols[(b & 0b1111) as usize] as char);
+        }
+    }
+
+    output
+}
+
+pub fn decode(input: &str) -> anyhow::Result<Vec<u8>> {
+    decode_with(input, &COMPONENT)
+}
+
+// only the escapes (and '+' for forms) are decoded, everything else is taken as is
+pub fn decode_with(input: &str, config: &Config) -> anyhow::Result<Vec<u8>> {
+    let input = input.as_bytes();
+    let mut output = Vec::with_capacity(input.len());
+
+    let mut i = 0;
+    while i < input.len() {
+        match input[i] {
+            ESCAPE => {
+                let value = |k: usize| input.get(i + k).and_then(|&b| hex::UPPER_ALPHABET.value(b));
+
+                let (Some(high), Some(low)) = (value(1), value(2)) else {
+                    let escape = String::from_utf8_lossy(&input[i..input.len().min(i + 3)]);
+                    return Err(anyhow!("invalid escape {escape:?} at offset {i}"));
+                };
+
+                output.push(high << 4 | low);
+                i += 3;
+            }
+            b'+' if config.form => {
+                output.push(b' ');
+                i += 1;
+            }
+            b => {
+                output.push(b);
+                i += 1;
+            }
+        }
+    }
+
+    Ok(output)
+}
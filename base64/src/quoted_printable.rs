@@ -0,0 +1,135 @@
+use crate::hex;
+use anyhow::anyhow;
+
+/*
+* RFC 2045 quoted-printable, printable ascii stays as is and everything else is
+* written as "=XX" in uppercase hex, so mostly ascii text stays readable:
+*
+*   "caf\xc3\xa9 = 4\n" -> "caf=C3=A9 =3D 4\n"
+*
+* lines are at most 76 columns, longer ones get a soft line break (a trailing '=')
+* that the decoder removes. spaces and tabs at the end of a line are encoded, since
+* mailers like to strip them.
+*
+* in text mode the line breaks of the input stay line breaks, in binary mode they're
+* bytes like any other and get encoded too
+*/
+
+const MAX_LINE: usize = 76;
+const ESCAPE: u8 = b'=';
+
+#[derive(Clone, Copy)]
+pub struct Config {
+    pub binary: bool,
+}
+
+pub const TEXT: Config = Config { binary: false };
+pub const BINARY: Config = Config { binary: true };
+
+fn is_literal(b: u8, at_line_end: bool) -> bool {
+    match b {
+        b' ' | b'\t' => !at_line_end,
+        ESCAPE => false,
+        b => b.is_ascii_graphic(),
+    }
+}
+
+fn encode_line(line: &[u8], output: &mut String) {
+    let symbols = hex::UPPER_ALPHABET.symbols();
+    let mut column = 0;
+
+    for (i, &b) in line.iter().enumerate() {
+        let last = i == line.len() - 1;
+        let literal = is_literal(b, last);
+        let len = if literal { 1 } else { 3 };
+
+        // the soft line break needs a column for its '=', unless this is the last token
+        let limit = if last { MAX_LINE } else { MAX_LINE - 1 };
+        if column + len > limit {
+            output.push_str("=\n");
+            column = 0;
+        }
+
+        if literal {
+            output.push(b as char);
+        } else {
+            output.push(ESCAPE as char);
+            output.push(symbols[(b >> 4) as usize] as char);
+            output.push(symbols[(b & 0b1111) as usize] as char);
+        }
+
+        column += len;
+    }
+}
+
+pub fn encode(input: &[u8]) -> String {
+    encode_with(input, &TEXT)
+}
+
+pub fn encode_with(input: &[u8], config: &Config) -> String {
+    let mut output = String::with_capacity(input.len() * 3 / 2);
+
+    if config.binary {
+        encode_line(input, &mut output);
+        return output;
+    }
+
+    let mut lines = input.split(|&b| b == b'\n').peekable();
+
+    while let Some(line) = lines.next() {
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        encode_line(line, &mut output);
+
+        if lines.peek().is_some() {
+            output.push('\n');
+        }
+    }
+
+    output
+}
+
+// soft line breaks are removed, and the hard ones come out as '\n'
+pub fn decode(input: &str) -> anyhow::Result<Vec<u8>> {
+    let mut output = Vec::with_capacity(input.len());
+    let mut lines = input.split('\n').enumerate().peekable();
+
+    while let Some((number, line)) = lines.next() {
+        let error = |message: String| anyhow!("line {}: {message}", number + 1);
+
+        // trailing whitespace was added in transit, it can't be part of the data
+        let line = line.trim_end_matches([' ', '\t', '\r']).as_bytes();
+
+        let (line, soft_break) = match line.strip_suffix(&[ESCAPE]) {
+            Some(line) => (line, true),
+            None => (line, false),
+        };
+
+        let mut i = 0;
+        while i < line.len() {
+            if line[i] != ESCAPE {
+                output.push(line[i]);
+                i += 1;
+                continue;
+            }
+
+            let value = |k: usize| line.get(i + k).and_then(|&b| hex::UPPER_ALPHABET.value(b));
+
+            let (Some(high), Some(low)) = (value(1), value(2)) else {
+                let escape = String::from_utf8_lossy(&line[i..line.len().min(i + 3)]);
+                return Err(error(format!(
+                    "invalid escape {escape:?} at column {}",
+                    i + 1
+                )));
+            };
+
+            output.push(high << 4 | low);
+            i += 3;
+        }
+
+        if !soft_break && lines.peek().is_some() {
+            output.push(b'\n');
+        }
+    }
+
+    Ok(output)
+}
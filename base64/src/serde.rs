@@ -0,0 +1,99 @@
+use ::serde::{de::Error as _, Deserialize, Deserializer, Serializer};
+
+/*
+* `#[serde(with = ...)]` modules that write byte fields as strings instead of arrays
+* of numbers:
+*
+*   #[derive(Serialize, Deserialize)]
+*   struct Key {
+*       #[serde(with = "base64::serde::base64")]
+*       public: Vec<u8>,
+*       #[serde(with = "base64::serde::hex")]
+*       fingerprint: [u8; 32],
+*   }
+*
+* anything that is `AsRef<[u8]>` can be serialized, and anything that can be made from
+* a `Vec<u8>` can be deserialized, fixed size arrays fail when the length doesn't match
+*/
+
+fn serialize<S: Serializer>(
+    bytes: &[u8],
+    encode: impl FnOnce(&[u8]) -> String,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&encode(bytes))
+}
+
+fn deserialize<'de, D, T>(
+    decode: impl FnOnce(&str) -> anyhow::Result<Vec<u8>>,
+    deserializer: D,
+) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: TryFrom<Vec<u8>>,
+{
+    let string = String::deserialize(deserializer)?;
+    let bytes = decode(&string).map_err(D::Error::custom)?;
+    let len = bytes.len();
+
+    T::try_from(bytes).map_err(|_| D::Error::custom(format!("{len} bytes don't fit the field")))
+}
+
+macro_rules! codec {
+    ($name:ident, $encode:expr, $decode:expr) => {
+        pub mod $name {
+            use super::*;
+
+            pub fn serialize<S, T>(bytes: &T, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+                T: AsRef<[u8]> + ?Sized,
+            {
+                super::serialize(bytes.as_ref(), $encode, serializer)
+            }
+
+            pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+            where
+                D: Deserializer<'de>,
+                T: TryFrom<Vec<u8>>,
+            {
+                super::deserialize($decode, deserializer)
+            }
+        }
+    };
+}
+
+codec!(
+    // "+/" with padding
+    base64,
+    crate::base64::encode,
+    crate::base64::decode
+);
+
+codec!(
+    // "+/" without padding
+    base64_no_pad,
+    |bytes| crate::base64::encode_with(bytes, &crate::base64::STANDARD_NO_PAD),
+    |string| crate::base64::decode_with(string, &crate::base64::STANDARD_NO_PAD)
+);
+
+codec!(
+    // "-_" with padding
+    base64_url,
+    |bytes| crate::base64::encode_with(bytes, &crate::base64::URL_SAFE),
+    |string| crate::base64::decode_with(string, &crate::base64::URL_SAFE)
+);
+
+codec!(
+    // what JWTs and most URL tokens use
+    base64_url_no_pad,
+    |bytes| crate::base64::encode_with(bytes, &crate::base64::URL_SAFE_NO_PAD),
+    |string| crate::base64::decode_with(string, &crate::base64::URL_SAFE_NO_PAD)
+);
+
+codec!(
+    // lowercase, decoding takes either case
+    hex,
+    crate::hex::encode,
+    crate::hex::decode
+);
@@ -0,0 +1,183 @@
+use crate::{alphabet, bits};
+use anyhow::{anyhow, bail, Context};
+
+/*
+* uuencode and xxencode, both split the input into lines of 45 bytes and write every
+* 3 bytes as 4 symbols of 6 bits, with the first symbol of each line being the number
+* of bytes on it:
+*
+*   begin 644 hey.txt
+*   $:&5Y"@``
+*   `
+*   end
+*
+* uuencode's symbols are the value + 32 (' ' to '_'), since trailing spaces tend to get
+* eaten by mailers, the backtick variant writes 0 as '`' instead. xxencode uses a
+* letters and digits alphabet that survives EBCDIC gateways.
+*
+* the last group of a line is padded with zero bytes, the length symbol tells how many
+* of them are real.
+*/
+
+pub type Alphabet = alphabet::Alphabet<64>;
+
+const fn uu_symbols(zero: u8) -> [u8; 64] {
+    let mut symbols = [0; 64];
+    symbols[0] = zero;
+
+    let mut i = 1;
+    while i < symbols.len() {
+        symbols[i] = b' ' + i as u8;
+        i += 1;
+    }
+
+    symbols
+}
+
+// both of the uu alphabets decode ' ' and '`' as 0
+pub const UU_ALPHABET: Alphabet = Alphabet::new(&uu_symbols(b' ')).with_alias(b'`', b' ');
+pub const UU_BACKTICK_ALPHABET: Alphabet = Alphabet::new(&uu_symbols(b'`')).with_alias(b' ', b'`');
+pub const XX_ALPHABET: Alphabet =
+    Alphabet::new(b"+-0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz");
+
+const LINE_BYTES: usize = 45;
+const DEFAULT_MODE: u32 = 0o644;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UuFile {
+    pub mode: u32,
+    pub name: String,
+    pub contents: Vec<u8>,
+}
+
+impl UuFile {
+    pub fn new(name: impl Into<String>, contents: Vec<u8>) -> Self {
+        UuFile {
+            mode: DEFAULT_MODE,
+            name: name.into(),
+            contents,
+        }
+    }
+}
+
+pub fn encode(file: &UuFile) -> String {
+    encode_with(file, &UU_ALPHABET)
+}
+
+pub fn encode_with(file: &UuFile, alphabet: &Alphabet) -> String {
+    let symbols = alphabet.symbols();
+    let mut output = format!("begin {:o} {}\n", file.mode, file.name);
+    let mut values = Vec::with_capacity(LINE_BYTES / 3 * 4);
+
+    for line in file.contents.chunks(LINE_BYTES) {
+        let mut padded = [0u8; LINE_BYTES];
+        let padded_len = line.len().next_multiple_of(3);
+        padded[..line.len()].copy_from_slice(line);
+
+        values.clear();
+        bits::regroup(&padded[..padded_len], 8, 6, false, &mut values)
+            .expect("bytes always fit in 8 bits");
+
+        output.push(symbols[line.len()] as char);
+        output.extend(values.iter().map(|&value| symbols[value as usize] as char));
+        output.push('\n');
+    }
+
+    // an empty line ends the data
+    output.push(symbols[0] as char);
+    output.push_str("\nend\n");
+
+    output
+}
+
+fn parse_header(line: &str) -> anyhow::Result<(u32, String)> {
+    let rest = line.strip_prefix("begin ").unwrap();
+    let (mode, name) = rest
+        .trim_start()
+        .split_once(' ')
+        .ok_or_else(|| anyhow!("the begin line is missing the file name: {line:?}"))?;
+
+    let mode =
+        u32::from_str_radix(mode, 8).with_context(|| format!("invalid file mode {mode:?}"))?;
+
+    Ok((mode, name.trim_end().to_string()))
+}
+
+pub fn decode(input: &str) -> anyhow::Result<UuFile> {
+    decode_with(input, &UU_ALPHABET)
+}
+
+// anything before the begin line (like mail headers) is skipped
+pub fn decode_with(input: &str, alphabet: &Alphabet) -> anyhow::Result<UuFile> {
+    let mut lines = input.lines().enumerate();
+
+    let (mode, name) = lines
+        .by_ref()
+        .find(|(_, line)| line.starts_with("begin "))
+        .map(|(_, line)| parse_header(line))
+        .ok_or_else(|| anyhow!("no begin line was found"))??;
+
+    let mut contents = Vec::new();
+    let mut line_values = Vec::with_capacity(LINE_BYTES / 3 * 4);
+    let mut ended = false;
+
+    for (number, line) in lines.by_ref() {
+        let line = line.trim_end_matches(['\r', '\n']);
+        let error = |message: String| anyhow!("line {}: {message}", number + 1);
+
+        let Some((&length, symbols)) = line.as_bytes().split_first() else {
+            // an empty line is a length symbol of ' ' that got trimmed
+            ended = true;
+            break;
+        };
+
+        let len = alphabet
+            .value(length)
+            .ok_or_else(|| error(format!("invalid length symbol {:?}", char::from(length))))?
+            as usize;
+
+        if len == 0 {
+            ended = true;
+            break;
+        }
+
+        if len > LINE_BYTES {
+            return Err(error(format!("{len} bytes is more than a line can hold")));
+        }
+
+        line_values.clear();
+        for (k, &symbol) in symbols.iter().enumerate() {
+            let value = alphabet.value(symbol).ok_or_else(|| {
+                error(format!(
+                    "invalid symbol {:?} at column {}",
+                    char::from(symbol),
+                    k + 2
+                ))
+            })?;
+            line_values.push(value);
+        }
+
+        // trailing spaces (zeros) often get stripped in transit, so put them back,
+        // and ignore anything past the groups the length needs (some encoders add a checksum)
+        let needed = len.div_ceil(3) * 4;
+        line_values.resize(needed, 0);
+
+        let start = contents.len();
+        bits::regroup(&line_values, 6, 8, false, &mut contents)?;
+        contents.truncate(start + len);
+    }
+
+    if !ended {
+        bail!("the data ended without the empty line and end");
+    }
+
+    if !lines.any(|(_, line)| line.trim_end() == "end") {
+        bail!("missing the end line");
+    }
+
+    Ok(UuFile {
+        mode,
+        name,
+        contents,
+    })
+}
@@ -0,0 +1,61 @@
+use crate::ascii85::{self, decode_group, encode_group};
+use anyhow::{anyhow, bail};
+
+/*
+* ZeroMQ's Z85 (RFC 32/Z85), the same 4 bytes to 5 digits as ascii85 but with an
+* alphabet that's safe to put in source code and config files, no 'z' shorthand,
+* no delimiters, and no partial groups, the input has to be a multiple of 4 bytes
+*/
+
+pub const ALPHABET: ascii85::Alphabet = ascii85::Alphabet::new(
+    b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ.-:+=^!/*?&<>()[]{}@%$#",
+);
+
+pub fn encode(input: &[u8]) -> anyhow::Result<String> {
+    if !input.len().is_multiple_of(4) {
+        bail!(
+            "z85 input must be a multiple of 4 bytes, got {}",
+            input.len()
+        );
+    }
+
+    Ok(input
+        .chunks_exact(4)
+        .flat_map(|chunk| encode_group(chunk.try_into().unwrap(), ALPHABET.symbols()))
+        .map(char::from)
+        .collect())
+}
+
+pub fn decode(input: &str) -> anyhow::Result<Vec<u8>> {
+    let input = input.as_bytes();
+
+    if !input.len().is_multiple_of(5) {
+        bail!(
+            "z85 input must be a multiple of 5 symbols, got {}",
+            input.len()
+        );
+    }
+
+    let mut output = Vec::with_capacity(input.len() / 5 * 4);
+
+    for (block, chunk) in input.chunks_exact(5).enumerate() {
+        let mut group = [0u8; 5];
+
+        for (k, (value, &symbol)) in group.iter_mut().zip(chunk).enumerate() {
+            *value = ALPHABET.value(symbol).ok_or_else(|| {
+                anyhow!(
+                    "invalid z85 symbol {:?} at offset {}",
+                    char::from(symbol),
+                    block * 5 + k
+                )
+            })?;
+        }
+
+        let n = decode_group(group)
+            .ok_or_else(|| anyhow!("the group at offset {} overflows 32 bits", block * 5))?;
+
+        output.extend_from_slice(&n.to_be_bytes());
+    }
+
+    Ok(output)
+}
@@ -0,0 +1,77 @@
+use base64::ascii85::{decode, encode, encode_with, ADOBE, BTOA};
+use base64::{hex, z85};
+
+#[test]
+fn vectors() {
+    assert_eq!(encode(b"Man "), "<~9jqo^~>");
+    assert_eq!(
+        encode_with(b"Man is distinguished", &BTOA),
+        "9jqo^BlbD-BleB1DJ+*+F(f,q"
+    );
+    assert_eq!(
+        decode("<~9jqo^BlbD-BleB1DJ+*+F(f,q~>").unwrap(),
+        b"Man is distinguished"
+    );
+
+    // a partial group only writes as many symbols as it needs
+    assert_eq!(encode_with(b".", &BTOA), "/c");
+    assert_eq!(decode("/c").unwrap(), b".");
+}
+
+#[test]
+fn zero_groups_are_z() {
+    assert_eq!(encode(&[0; 8]), "<~zz~>");
+    assert_eq!(decode("<~zz~>").unwrap(), [0; 8]);
+
+    // only whole groups, a partial one of zeros is written out
+    assert_eq!(encode(&[0; 5]), "<~z!!~>");
+    assert_eq!(decode("z!!").unwrap(), [0; 5]);
+
+    assert!(decode("9jz").is_err());
+}
+
+#[test]
+fn space_groups_are_y_in_btoa() {
+    assert_eq!(encode_with(b"    ", &BTOA), "y");
+    assert_eq!(encode_with(b"    ", &ADOBE), "<~+<VdL~>");
+
+    assert_eq!(decode("y").unwrap(), b"    ");
+    assert_eq!(decode("<~+<VdL~>").unwrap(), b"    ");
+    assert_eq!(decode("yz").unwrap(), b"    \0\0\0\0");
+
+    assert!(decode("9jy").is_err());
+}
+
+#[test]
+fn round_trips() {
+    let input: Vec<u8> = (0..=255).chain([0; 9]).chain(*b"        ").collect();
+
+    for config in [&ADOBE, &BTOA] {
+        for len in 0..input.len() {
+            let encoded = encode_with(&input[..len], config);
+            assert_eq!(decode(&encoded).unwrap(), &input[..len], "{encoded}");
+        }
+    }
+}
+
+#[test]
+fn invalid_input_is_an_error() {
+    // 'v' is past the alphabet, "s8W-!" is u32::MAX and "s8W-\"" one more
+    for input in ["v", "s8W-\"", "9jqo^9", "<~9jqo^~"] {
+        assert!(decode(input).is_err(), "{input}");
+    }
+    assert_eq!(decode("s8W-!").unwrap(), [0xff; 4]);
+}
+
+#[test]
+fn z85_vectors() {
+    let input = hex::decode("864FD26FB559F75B").unwrap();
+
+    assert_eq!(z85::encode(&input).unwrap(), "HelloWorld");
+    assert_eq!(z85::decode("HelloWorld").unwrap(), input);
+
+    assert!(z85::encode(b"abc").is_err());
+    assert!(z85::decode("Hello").is_ok());
+    assert!(z85::decode("HelloWorl").is_err());
+    assert!(z85::decode("Hello~orld").is_err());
+}
@@ -0,0 +1,76 @@
+use base64::base32::{
+    decode_crockford, decode_with, encode_crockford, encode_with, HEX, HEX_NO_PAD, STANDARD,
+    STANDARD_NO_PAD,
+};
+
+#[test]
+fn rfc_4648_vectors() {
+    let vectors = [
+        ("", "", ""),
+        ("f", "MY======", "CO======"),
+        ("fo", "MZXQ====", "CPNG===="),
+        ("foo", "MZXW6===", "CPNMU==="),
+        ("foob", "MZXW6YQ=", "CPNMUOG="),
+        ("fooba", "MZXW6YTB", "CPNMUOJ1"),
+        ("foobar", "MZXW6YTBOI======", "CPNMUOJ1E8======"),
+    ];
+
+    for (input, standard, hex) in vectors {
+        assert_eq!(encode_with(input.as_bytes(), &STANDARD), standard);
+        assert_eq!(encode_with(input.as_bytes(), &HEX), hex);
+
+        assert_eq!(decode_with(standard, &STANDARD).unwrap(), input.as_bytes());
+        assert_eq!(decode_with(hex, &HEX).unwrap(), input.as_bytes());
+
+        let unpadded = standard.trim_end_matches('=');
+        assert_eq!(encode_with(input.as_bytes(), &STANDARD_NO_PAD), unpadded);
+        assert_eq!(
+            decode_with(unpadded, &STANDARD_NO_PAD).unwrap(),
+            input.as_bytes()
+        );
+        assert_eq!(
+            decode_with(hex.trim_end_matches('='), &HEX_NO_PAD).unwrap(),
+            input.as_bytes()
+        );
+    }
+}
+
+#[test]
+fn decoding_is_case_insensitive() {
+    assert_eq!(
+        decode_with("mzxw6ytboi======", &STANDARD).unwrap(),
+        b"foobar"
+    );
+}
+
+#[test]
+fn invalid_input_is_an_error() {
+    for input in ["MZXW6YTBOI=====", "MZXW6YT1", "M======="] {
+        assert!(decode_with(input, &STANDARD).is_err(), "{input}");
+    }
+}
+
+#[test]
+fn crockford_check_symbol() {
+    assert_eq!(encode_crockford(b"foobar", false), "CSQPYRK1E8");
+    assert_eq!(encode_crockford(b"foobar", true), "CSQPYRK1E86");
+    assert_eq!(decode_crockford("CSQPYRK1E86", true).unwrap(), b"foobar");
+
+    // the check values past 31 have their own symbols
+    assert_eq!(encode_crockford(&[32], true), "40*");
+    assert_eq!(decode_crockford("40*", true).unwrap(), [32]);
+    assert_eq!(encode_crockford(&[36], true), "4GU");
+
+    assert!(decode_crockford("CSQPYRK1E85", true).is_err());
+    assert!(decode_crockford("", true).is_err());
+}
+
+#[test]
+fn crockford_folds_lookalikes_and_skips_hyphens() {
+    assert_eq!(decode_crockford("csqp-yrk1-e8", false).unwrap(), b"foobar");
+    assert_eq!(
+        decode_crockford("CSQPYRKIE8", false).unwrap(),
+        decode_crockford("CSQPYRKLE8", false).unwrap()
+    );
+    assert_eq!(decode_crockford("O0", false).unwrap(), [0]);
+}
@@ -0,0 +1,54 @@
+use base64::base58::{
+    decode, decode_check, decode_with, encode, encode_check, encode_with, FLICKR_ALPHABET,
+    RIPPLE_ALPHABET,
+};
+use base64::hex;
+
+#[test]
+fn vectors() {
+    let vectors: [(&[u8], &str); 5] = [
+        (b"", ""),
+        (b"Hello World!", "2NEpo7TZRRrLZSi2U"),
+        (
+            b"The quick brown fox jumps over the lazy dog.",
+            "USm3fpXnKG5EUBx2ndxBDMPVciP5hGey2Jh4NDv6gmeo1LkMeiKrLJUUBk6Z",
+        ),
+        (&[0, 0, 0x28, 0x7f, 0xb4, 0xcd], "11233QC4"),
+        (&[0; 3], "111"),
+    ];
+
+    for (input, expected) in vectors {
+        assert_eq!(encode(input), expected);
+        assert_eq!(decode(expected).unwrap(), input);
+    }
+}
+
+#[test]
+fn base58check_vector() {
+    let payload = hex::decode("00f54a5851e9372b87810a8e60cdd2e7cfd80b6e31").unwrap();
+
+    assert_eq!(encode_check(&payload), "1PMycacnJaSqwwJqjawXBErnLsZ7RkXUAs");
+    assert_eq!(
+        decode_check("1PMycacnJaSqwwJqjawXBErnLsZ7RkXUAs").unwrap(),
+        payload
+    );
+
+    // one symbol off breaks the checksum
+    assert!(decode_check("1PMycacnJaSqwwJqjawXBErnLsZ7RkXUAt").is_err());
+    assert!(decode_check("1111").is_err());
+}
+
+#[test]
+fn other_alphabets_round_trip() {
+    let input: Vec<u8> = (0..=255).collect();
+
+    for alphabet in [&RIPPLE_ALPHABET, &FLICKR_ALPHABET] {
+        let encoded = encode_with(&input, alphabet);
+        assert_eq!(decode_with(&encoded, alphabet).unwrap(), input);
+    }
+
+    // the bitcoin alphabet leaves out 0, O, I and l
+    for input in ["0", "O", "I", "l", "2NEpo7TZRRrLZSi2U!"] {
+        assert!(decode(input).is_err(), "{input}");
+    }
+}
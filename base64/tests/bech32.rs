@@ -0,0 +1,188 @@
+use base64::bech32::{decode, encode, encode_segwit, Variant, Witness};
+use base64::hex;
+
+// BIP-173 and BIP-350
+const VALID: [(&str, Variant); 12] = [
+    ("A12UEL5L", Variant::Bech32),
+    ("a12uel5l", Variant::Bech32),
+    ("an83characterlonghumanreadablepartthatcontainsthenumber1andtheexcludedcharactersbio1tt5tgs", Variant::Bech32),
+    ("abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw", Variant::Bech32),
+    ("split1checkupstagehandshakeupstreamerranterredcaperred2y9e3w", Variant::Bech32),
+    ("?1ezyfcl", Variant::Bech32),
+    ("A1LQFN3A", Variant::Bech32m),
+    ("a1lqfn3a", Variant::Bech32m),
+    ("an83characterlonghumanreadablepartthatcontainsthetheexcludedcharactersbioandnumber11sg7hg6", Variant::Bech32m),
+    ("abcdef1l7aum6echk45nj3s0wdvt2fg8x9yrzpqzd3ryx", Variant::Bech32m),
+    ("split1checkupstagehandshakeupstreamerranterredcaperredlc445v", Variant::Bech32m),
+    ("?1v759aa", Variant::Bech32m),
+];
+
+const INVALID: [&str; 26] = [
+    "\x201nwldj5",
+    "\x7f1axkwrx",
+    "\u{80}1eym55h",
+    "an84characterslonghumanreadablepartthatcontainsthenumber1andtheexcludedcharactersbio1569pvx",
+    "pzry9x0s0muk",
+    "1pzry9x0s0muk",
+    "x1b4n0q5v",
+    "li1dgmt3",
+    "de1lg7wt\u{ff}",
+    "A1G7SGD8",
+    "10a06t8",
+    "1qzzfhee",
+    "\x201xj0phk",
+    "\x7f1g6xzxy",
+    "\u{80}1vctc34",
+    "an84characterslonghumanreadablepartthatcontainsthetheexcludedcharactersbioandnumber11d6pts4",
+    "qyrz8wqd2c9m",
+    "1qyrz8wqd2c9m",
+    "y1b0jsk6g",
+    "lt1igcx5c0",
+    "in1muywd",
+    "mm1crxm3i",
+    "au1s5cgom",
+    "M1VUXWEZ",
+    "16plkw9",
+    "1p2gdwpf",
+];
+
+// the address and its scriptPubKey, the version opcode, the program length and the program
+const SEGWIT: [(&str, &str); 8] = [
+    (
+        "BC1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7KV8F3T4",
+        "0014751e76e8199196d454941c45d1b3a323f1433bd6",
+    ),
+    (
+        "tb1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3q0sl5k7",
+        "00201863143c14c5166804bd19203356da136c985678cd4d27a1b8c6329604903262",
+    ),
+    (
+        "bc1pw508d6qejxtdg4y5r3zarvary0c5xw7kw508d6qejxtdg4y5r3zarvary0c5xw7kt5nd6y",
+        "5128751e76e8199196d454941c45d1b3a323f1433bd6751e76e8199196d454941c45d1b3a323f1433bd6",
+    ),
+    ("BC1SW50QGDZ25J", "6002751e"),
+    (
+        "bc1zw508d6qejxtdg4y5r3zarvaryvaxxpcs",
+        "5210751e76e8199196d454941c45d1b3a323",
+    ),
+    (
+        "tb1qqqqqp399et2xygdj5xreqhjjvcmzhxw4aywxecjdzew6hylgvsesrxh6hy",
+        "0020000000c4a5cad46221b2a187905e5266362b99d5e91c6ce24d165dab93e86433",
+    ),
+    (
+        "tb1pqqqqp399et2xygdj5xreqhjjvcmzhxw4aywxecjdzew6hylgvsesf3hn0c",
+        "5120000000c4a5cad46221b2a187905e5266362b99d5e91c6ce24d165dab93e86433",
+    ),
+    (
+        "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0",
+        "512079be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+    ),
+];
+
+const INVALID_SEGWIT: [&str; 14] = [
+    // the wrong checksum for the version
+    "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqh2y7hd",
+    "tb1z0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqglt7rf",
+    "BC1S0XLXVLHEMJA6C4DQV22UAPCTQUPFHLXM9H8Z3K2E72Q4K9HCZ7VQ54WELL",
+    "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kemeawh",
+    "tb1q0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vq24jc47",
+    "bc1p38j9r5y49hruaue7wxjce0updqjuyyx0kh56v8s25huc6995vvpql3jow4",
+    // version 17
+    "BC130XLXVLHEMJA6C4DQV22UAPCTQUPFHLXM9H8Z3K2E72Q4K9HCZ7VQ7ZWS8R",
+    // programs of 1 and 41 bytes, and 16 for version 0
+    "bc1pw5dgrnzv",
+    "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7v8n0nx0muaewav253zgeav",
+    "BC1QR508D6QEJXTDG4Y5R3ZARVARYV98GJ9P",
+    "tb1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vq47Zagq",
+    // too much padding, and padding that isn't zero
+    "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7v07qwwzcrf",
+    "tb1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vpggkg4j",
+    "bc1gmk9yu",
+];
+
+#[test]
+fn valid_checksums() {
+    // the longest strings allowed, 90 characters
+    let longest = [
+        (format!("11{}c8247j", "q".repeat(82)), Variant::Bech32),
+        (format!("11{}ludsr8", "l".repeat(82)), Variant::Bech32m),
+    ];
+
+    for (input, variant) in VALID
+        .map(|(input, variant)| (input.to_string(), variant))
+        .into_iter()
+        .chain(longest)
+    {
+        let decoded = decode(&input).unwrap_or_else(|e| panic!("{input}: {e}"));
+        assert_eq!(decoded.variant, variant, "{input}");
+    }
+}
+
+#[test]
+fn invalid_strings() {
+    for input in INVALID {
+        assert!(decode(input).is_err(), "{input:?}");
+    }
+}
+
+#[test]
+fn segwit_addresses() {
+    for (address, script) in SEGWIT {
+        let decoded = decode(address).unwrap();
+        let witness = decoded
+            .witness()
+            .unwrap_or_else(|e| panic!("{address}: {e}"));
+
+        let script = hex::decode(script).unwrap();
+        let version = match script[0] {
+            0 => 0,
+            opcode => opcode - 0x50,
+        };
+        assert_eq!(witness.version, version, "{address}");
+        assert_eq!(witness.program, script[2..], "{address}");
+
+        let encoded = encode_segwit(&decoded.hrp, &witness).unwrap();
+        assert_eq!(encoded, address.to_ascii_lowercase());
+    }
+}
+
+#[test]
+fn invalid_segwit_addresses() {
+    for address in INVALID_SEGWIT {
+        let witness = decode(address).and_then(|decoded| decoded.witness());
+        assert!(witness.is_err(), "{address}");
+    }
+
+    let witness = |version, len| Witness {
+        version,
+        program: vec![0; len],
+    };
+    assert!(encode_segwit("bc", &witness(17, 20)).is_err());
+    assert!(encode_segwit("bc", &witness(0, 21)).is_err());
+    assert!(encode_segwit("bc", &witness(1, 1)).is_err());
+}
+
+#[test]
+fn bytes_round_trip() {
+    assert_eq!(
+        encode("test", b"hello", Variant::Bech32).unwrap(),
+        "test1dpjkcmr09ys0qs"
+    );
+    assert_eq!(
+        encode("test", b"hello", Variant::Bech32m).unwrap(),
+        "test1dpjkcmr0scqr9j"
+    );
+
+    for variant in [Variant::Bech32, Variant::Bech32m] {
+        for len in 0..=50 {
+            let input: Vec<u8> = (0..len).collect();
+            let decoded = decode(&encode("hrp", &input, variant).unwrap()).unwrap();
+
+            assert_eq!(decoded.variant, variant);
+            assert_eq!(decoded.bytes().unwrap(), input);
+        }
+    }
+
+    // a segwit address doesn't regroup as plain bytes
+    assert!(decode(SEGWIT[0].0).unwrap().bytes().is_err());
+}
@@ -0,0 +1,91 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn run(args: &[&str], stdin: &[u8]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_base64"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    child.stdin.take().unwrap().write_all(stdin).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(args: &[&str], stdin: &[u8]) -> Vec<u8> {
+    let output = run(args, stdin);
+    assert!(
+        output.status.success(),
+        "{args:?}: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    output.stdout
+}
+
+#[test]
+fn lines_wrap_at_76_by_default() {
+    let encoded = stdout(&[], &[0; 100]);
+    let lines: Vec<usize> = encoded.split(|&b| b == b'\n').map(<[u8]>::len).collect();
+
+    assert_eq!(lines, [76, 60, 0]);
+    assert_eq!(stdout(&[], b""), b"");
+}
+
+#[test]
+fn wrap_widths() {
+    assert_eq!(stdout(&["-w", "0"], &[0; 99]), [b'A'; 132]);
+    assert_eq!(stdout(&["-w", "0"], b"hi"), b"aGk=");
+    assert_eq!(stdout(&["--wrap", "4"], b"hello"), b"aGVs\nbG8=\n");
+}
+
+#[test]
+fn concatenated_padding_decodes_as_one() {
+    assert_eq!(stdout(&["-d"], b"aGk=aGk="), b"hihi");
+    assert_eq!(stdout(&["-d"], b"aGk=\naGk=\n"), b"hihi");
+    assert_eq!(stdout(&["-d"], b"aGVs\nbG8=\n"), b"hello");
+}
+
+#[test]
+fn garbage_needs_ignore_garbage() {
+    assert!(!run(&["-d"], b"aG*k=\n").status.success());
+    assert!(!run(&["-d"], b"aG k=\n").status.success());
+
+    assert_eq!(stdout(&["-d", "-i"], b"aG*k=\n"), b"hi");
+    assert_eq!(stdout(&["-d", "--ignore-garbage"], b"a G\tk=\r\n"), b"hi");
+}
+
+#[test]
+fn basenc_encodings() {
+    let cases: [(&str, &[u8], &[u8]); 3] = [
+        ("--base64url", b"\xfb\xff", b"-_8=\n"),
+        ("--base32", b"foobar", b"MZXW6YTBOI======\n"),
+        ("--base16", b"\xde\xad\xbe\xef", b"DEADBEEF\n"),
+    ];
+
+    for (flag, input, encoded) in cases {
+        assert_eq!(stdout(&[flag], input), encoded, "{flag}");
+        assert_eq!(stdout(&[flag, "-d"], encoded), input, "{flag}");
+    }
+
+    assert!(!run(&["--base32", "--base16"], b"").status.success());
+}
+
+#[test]
+fn decoded_output_has_no_newline_added() {
+    assert_eq!(stdout(&["base32", "-d"], b"MZXW6YTBOI======\n"), b"foobar");
+    assert_eq!(stdout(&["hex", "-d"], b"6869\n"), b"hi");
+}
+
+#[test]
+fn base16_is_strict_like_basenc() {
+    assert_eq!(stdout(&["--base16", "-d"], b"4A4B\n4C\n"), b"JKL");
+
+    for input in [&b"0x4A4B"[..], b"4A 4B", b"4a4b", b"4A4"] {
+        let output = run(&["--base16", "-d"], input);
+        assert!(!output.status.success(), "{input:?}");
+        assert!(output.stdout.is_empty());
+    }
+}
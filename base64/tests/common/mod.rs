@@ -0,0 +1,15 @@
+// xorshift, so the data isn't all the same byte and we don't need a rand dependency.
+// the tests and the benchmark both use it, the benchmark through a #[path] module
+pub fn random_bytes(len: usize, seed: u64) -> Vec<u8> {
+    // a state of 0 would stay 0
+    let mut state = seed | 1;
+
+    (0..len)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state as u8
+        })
+        .collect()
+}
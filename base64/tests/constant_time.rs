@@ -0,0 +1,77 @@
+mod common;
+
+use base64::base64::{
+    decode_ct, decode_ct_slice_with, decode_ct_with, decode_slice_with, decode_with, encode_with,
+    Config, STANDARD, STANDARD_NO_PAD, URL_SAFE, URL_SAFE_NO_PAD,
+};
+use common::random_bytes;
+
+const CONFIGS: [Config; 4] = [STANDARD, STANDARD_NO_PAD, URL_SAFE, URL_SAFE_NO_PAD];
+
+#[test]
+fn matches_the_table_decoder_on_valid_input() {
+    for config in &CONFIGS {
+        for len in 0..300 {
+            let input = random_bytes(len, len as u64);
+            let encoded = encode_with(&input, config);
+
+            let expected = decode_with(&encoded, config).unwrap();
+            assert_eq!(decode_ct_with(&encoded, config).unwrap(), expected);
+            assert_eq!(expected, input);
+        }
+    }
+}
+
+#[test]
+fn matches_the_table_decoder_on_every_symbol() {
+    // every byte value in every position of a block, valid or not
+    for config in &CONFIGS {
+        for position in 0..8 {
+            for symbol in 0..=255u8 {
+                let mut encoded = *b"QUJDREVG";
+                encoded[position] = symbol;
+
+                let mut expected = [0u8; 6];
+                let mut actual = [0u8; 6];
+
+                let expected = decode_slice_with(&encoded, &mut expected, config)
+                    .map(|len| expected[..len].to_vec());
+                let actual = decode_ct_slice_with(&encoded, &mut actual, config)
+                    .map(|len| actual[..len].to_vec());
+
+                match (expected, actual) {
+                    (Ok(expected), Ok(actual)) => assert_eq!(expected, actual),
+                    (Err(_), Err(_)) => {}
+                    (expected, actual) => panic!(
+                        "symbol {symbol} at {position}: table {expected:?}, constant time {actual:?}"
+                    ),
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn rejects_what_the_table_decoder_rejects() {
+    for input in [
+        "QQ",
+        "Q===",
+        "QUJD=",
+        "QUJDR",
+        "QU=D",
+        "QUJ$",
+        "QUJDQUJD$UJD",
+        "ä",
+    ] {
+        assert!(decode_with(input, &STANDARD).is_err(), "{input}");
+        assert!(decode_ct(input).is_err(), "{input}");
+    }
+}
+
+#[test]
+fn clears_the_output_on_failure() {
+    let mut output = [0xAAu8; 9];
+
+    assert!(decode_ct_slice_with(b"c2VjcmV0$2V5", &mut output, &STANDARD).is_err());
+    assert_eq!(output, [0; 9]);
+}
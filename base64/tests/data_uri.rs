@@ -0,0 +1,94 @@
+use base64::data_uri::{encode, encode_with, parse, sniff_media_type, DataUri};
+
+const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+
+#[test]
+fn sniffing() {
+    assert_eq!(sniff_media_type(PNG), "image/png");
+    assert_eq!(
+        sniff_media_type(b"\xff\xd8\xff\xe0\0\x10JFIF"),
+        "image/jpeg"
+    );
+    assert_eq!(sniff_media_type(b"GIF89a\x01\0"), "image/gif");
+    assert_eq!(sniff_media_type(b"GIF87a\x01\0"), "image/gif");
+    assert_eq!(
+        sniff_media_type(b"<?xml version=\"1.0\"?>\n<!-- a comment -->\n<svg xmlns=\"http://www.w3.org/2000/svg\"/>"),
+        "image/svg+xml"
+    );
+    assert_eq!(sniff_media_type(b" {\"a\": [1, 2]}\n"), "application/json");
+    assert_eq!(sniff_media_type(b"[1, 2]"), "application/json");
+    assert_eq!(
+        sniff_media_type("caf\u{e9}\n".as_bytes()),
+        "text/plain;charset=utf-8"
+    );
+    assert_eq!(sniff_media_type(b"\0\x01\x02"), "application/octet-stream");
+    assert_eq!(sniff_media_type(b"\xc3\x28"), "application/octet-stream");
+}
+
+#[test]
+fn encoding() {
+    assert_eq!(encode(b"hi"), "data:text/plain;charset=utf-8;base64,aGk=");
+    assert_eq!(
+        encode_with(b"hi", "text/x-custom"),
+        "data:text/x-custom;base64,aGk="
+    );
+
+    let uri = parse(&encode(PNG)).unwrap();
+    assert_eq!(uri.mime(), "image/png");
+    assert_eq!(uri.data, PNG);
+}
+
+#[test]
+fn parsing() {
+    let parsed = |uri: &str| parse(uri).unwrap();
+
+    assert_eq!(
+        parsed("data:,hello%20world"),
+        DataUri {
+            media_type: "text/plain;charset=US-ASCII".into(),
+            data: b"hello world".to_vec(),
+        }
+    );
+    assert_eq!(
+        parsed("data:;charset=utf-8,caf%C3%A9").media_type,
+        "text/plain;charset=utf-8"
+    );
+    assert_eq!(parsed("DATA:text/plain;BASE64,aGk=").data, b"hi");
+
+    // missing padding, whitespace and percent-encoded symbols in base64 data
+    assert_eq!(parsed("data:text/plain;base64,aGk").data, b"hi");
+    assert_eq!(parsed(" data:text/plain;base64,aG\nk= ").data, b"hi");
+    assert_eq!(parsed("data:text/plain;base64,aGk%3D").data, b"hi");
+
+    let uri = parsed("data:text/html;charset=utf-8,%3Cb%3E");
+    assert_eq!(uri.mime(), "text/html");
+    assert_eq!(uri.data, b"<b>");
+}
+
+#[test]
+fn non_ascii_parameters() {
+    // the last 7 bytes of the header start inside the 'é'
+    let uri = parse("data:text/plain;name=\u{e9}123456,x").unwrap();
+    assert_eq!(uri.media_type, "text/plain;name=\u{e9}123456");
+    assert_eq!(uri.data, b"x");
+
+    let uri = parse("data:text/plain;name=caf\u{e9};base64,aGk=").unwrap();
+    assert_eq!(uri.media_type, "text/plain;name=caf\u{e9}");
+    assert_eq!(uri.data, b"hi");
+
+    assert!(parse("data:\u{e9}123456,x").is_err());
+}
+
+#[test]
+fn invalid_uris_are_errors() {
+    for uri in [
+        "http://example.com",
+        "data:text/plain;base64",
+        "data:text/plain;base64,a$k=",
+        "data:plain,hi",
+        "data:,%G0",
+        "dat",
+    ] {
+        assert!(parse(uri).is_err(), "{uri}");
+    }
+}
@@ -0,0 +1,73 @@
+use base64::detect::{detect, Encoding};
+use base64::{base32, base58, hex};
+
+fn ranking(input: &str) -> Vec<Encoding> {
+    detect(input)
+        .iter()
+        .map(|candidate| candidate.encoding)
+        .collect()
+}
+
+#[test]
+fn the_smallest_alphabet_that_fits_wins() {
+    let candidates = detect("deadbeef");
+
+    assert_eq!(candidates[0].encoding, Encoding::Hex);
+    assert_eq!(candidates[0].decoded, [0xde, 0xad, 0xbe, 0xef]);
+    assert!(candidates[0].confidence > 0.99);
+    assert_eq!(candidates[1].encoding, Encoding::Base32);
+
+    let total: f64 = candidates
+        .iter()
+        .map(|candidate| candidate.confidence)
+        .sum();
+    assert!((total - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn each_encoding_is_recognized() {
+    let input = b"Hello World, and a few more bytes to go with it";
+
+    assert_eq!(ranking(&hex::encode(input))[0], Encoding::Hex);
+    assert_eq!(ranking(&base32::encode(input))[0], Encoding::Base32);
+    assert_eq!(ranking("JBSWY3DPEBLW64TMMQ======")[0], Encoding::Base32);
+    assert_eq!(ranking(&base64::base64::encode(input))[0], Encoding::Base64);
+    assert_eq!(ranking("-_-_")[0], Encoding::Base64Url);
+    assert_eq!(
+        ranking("<~9jqo^BlbD-BleB1DJ+*+F(f,q~>"),
+        [Encoding::Ascii85]
+    );
+
+    // base58check outweighs base64's prior, plain base58 doesn't
+    let address = "1PMycacnJaSqwwJqjawXBErnLsZ7RkXUAs";
+    let candidates = detect(address);
+    assert_eq!(candidates[0].encoding, Encoding::Base58);
+    assert!(candidates[0].checksum);
+    assert_eq!(
+        ranking(&base58::encode(b"no checksum here"))[0],
+        Encoding::Base64
+    );
+}
+
+#[test]
+fn whitespace_and_mixed_case() {
+    assert_eq!(detect("de ad\nbe ef")[0].decoded, [0xde, 0xad, 0xbe, 0xef]);
+
+    // an encoder only writes one case, so mixed case makes hex less likely
+    let mixed = detect("DeadBeef");
+    assert_eq!(mixed[0].encoding, Encoding::Hex);
+    assert!(mixed[0].confidence < detect("deadbeef")[0].confidence);
+}
+
+#[test]
+fn nothing_decodes() {
+    assert!(detect("~~~").is_empty());
+}
+
+#[test]
+fn long_inputs_skip_base58() {
+    let input = "abcdefgh".repeat(100);
+
+    assert!(ranking(&input).contains(&Encoding::Base58));
+    assert!(!ranking(&input.repeat(10)).contains(&Encoding::Base58));
+}
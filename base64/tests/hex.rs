@@ -0,0 +1,64 @@
+use base64::hex::{decode, decode_with, dump, encode, encode_with, undump, BASE16, LOWER, UPPER};
+
+#[test]
+fn formatting_options() {
+    assert_eq!(encode(b"\xde\xad\xbe\xef"), "deadbeef");
+    assert_eq!(
+        encode_with(
+            b"\xde\xad\xbe\xef",
+            &UPPER.with_separator(':').with_prefix(true)
+        ),
+        "0xDE:AD:BE:EF"
+    );
+
+    assert_eq!(decode("DE AD be ef").unwrap(), b"\xde\xad\xbe\xef");
+    assert_eq!(decode("0xdeadbeef\n").unwrap(), b"\xde\xad\xbe\xef");
+    assert_eq!(
+        decode_with("de:ad:be:ef", &LOWER.with_separator(':')).unwrap(),
+        b"\xde\xad\xbe\xef"
+    );
+
+    assert!(decode("abc").is_err());
+    assert!(decode("zz").is_err());
+}
+
+#[test]
+fn dumps_look_like_xxd() {
+    assert_eq!(
+        dump(b"hello world, this is more than 16"),
+        "00000000: 6865 6c6c 6f20 776f 726c 642c 2074 6869  hello world, thi\n\
+         00000010: 7320 6973 206d 6f72 6520 7468 616e 2031  s is more than 1\n\
+         00000020: 36                                       6\n"
+    );
+}
+
+#[test]
+fn dumps_round_trip() {
+    let input: Vec<u8> = (0..=255).chain(b"  two spaces  ".iter().copied()).collect();
+
+    for len in [0, 1, 15, 16, 17, input.len()] {
+        assert_eq!(undump(&dump(&input[..len])).unwrap(), &input[..len]);
+    }
+}
+
+#[test]
+fn undump_zero_fills_gaps_but_not_forever() {
+    assert_eq!(undump("00000004: 6869  hi").unwrap(), b"\0\0\0\0hi");
+
+    for offset in ["ffffffffffffffff", "fffffffffffffffe", "7fffffff"] {
+        let line = format!("{offset}: 6869  hi");
+        assert!(undump(&line).is_err(), "{offset}");
+    }
+}
+
+#[test]
+fn base16_takes_only_uppercase_symbols() {
+    assert_eq!(
+        decode_with("DEADBEEF", &BASE16).unwrap(),
+        [0xde, 0xad, 0xbe, 0xef]
+    );
+
+    for input in ["deadbeef", "0xDEADBEEF", "DE AD", "DEADBEEF\n", "DEA"] {
+        assert!(decode_with(input, &BASE16).is_err(), "{input:?}");
+    }
+}
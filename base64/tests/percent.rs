@@ -0,0 +1,48 @@
+use base64::percent::{
+    decode, decode_with, encode, encode_with, Config, COMPONENT, FORM, PATH, QUERY,
+};
+
+#[test]
+fn components() {
+    let input = "a b/c?d=\u{e9}#e:f@g".as_bytes();
+
+    assert_eq!(encode(input), "a%20b%2Fc%3Fd%3D%C3%A9%23e%3Af%40g");
+    assert_eq!(encode_with(input, &PATH), "a%20b/c%3Fd=%C3%A9%23e:f@g");
+    assert_eq!(encode_with(input, &QUERY), "a%20b/c?d=%C3%A9%23e:f@g");
+    assert_eq!(
+        encode_with(input, &FORM),
+        "a+b%2Fc%3Fd%3D%C3%A9%23e%3Af%40g"
+    );
+
+    // the unreserved characters are never encoded
+    let unreserved = b"AZaz09-._~";
+    for config in [&COMPONENT, &PATH, &QUERY, &FORM] {
+        assert_eq!(encode_with(unreserved, config).as_bytes(), unreserved);
+    }
+
+    let custom = Config::new().keep(b"/");
+    assert_eq!(encode_with(b"a/b c", &custom), "a/b%20c");
+}
+
+#[test]
+fn decoding() {
+    assert_eq!(decode("a%20b%2fc").unwrap(), b"a b/c");
+    assert_eq!(decode("a+b").unwrap(), b"a+b");
+    assert_eq!(decode_with("a+b%2B", &FORM).unwrap(), b"a b+");
+
+    for input in ["%", "%2", "%G0", "abc%2"] {
+        assert!(decode(input).is_err(), "{input}");
+    }
+}
+
+#[test]
+fn round_trips() {
+    let input: Vec<u8> = (0..=255).collect();
+
+    for config in [&COMPONENT, &PATH, &QUERY, &FORM] {
+        assert_eq!(
+            decode_with(&encode_with(&input, config), config).unwrap(),
+            input
+        );
+    }
+}
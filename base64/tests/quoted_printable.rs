@@ -0,0 +1,60 @@
+use base64::quoted_printable::{decode, encode, encode_with, BINARY};
+
+#[test]
+fn vectors() {
+    assert_eq!(encode("caf\u{e9} = 4\n".as_bytes()), "caf=C3=A9 =3D 4\n");
+    assert_eq!(
+        decode("caf=C3=A9 =3D 4\n").unwrap(),
+        "caf\u{e9} = 4\n".as_bytes()
+    );
+
+    // spaces and tabs at the end of a line are encoded
+    assert_eq!(encode(b"end \nof\tline\t"), "end=20\nof\tline=09");
+    assert_eq!(encode(b"crlf\r\nlines"), "crlf\nlines");
+
+    assert_eq!(encode_with(b"a\nb", &BINARY), "a=0Ab");
+}
+
+#[test]
+fn lines_are_soft_broken_at_76_columns() {
+    let input = "x".repeat(200);
+    let encoded = encode(input.as_bytes());
+    let lines: Vec<&str> = encoded.lines().collect();
+
+    assert_eq!(
+        lines.iter().map(|line| line.len()).collect::<Vec<_>>(),
+        [76, 76, 50]
+    );
+    assert!(lines[..2].iter().all(|line| line.ends_with('=')));
+    assert_eq!(decode(&encoded).unwrap(), input.as_bytes());
+
+    // a line of exactly 76 needs no break, and an escape is never split by one
+    assert_eq!(encode("x".repeat(76).as_bytes()), "x".repeat(76));
+    let encoded = encode(&[0xff; 30]);
+    assert!(encoded.lines().all(|line| line.len() <= 76));
+    assert!(encoded
+        .lines()
+        .all(|line| line.trim_end_matches('=').len() % 3 == 0));
+    assert_eq!(decode(&encoded).unwrap(), [0xff; 30]);
+}
+
+#[test]
+fn round_trips() {
+    let input: Vec<u8> = (0..=255).chain(*b"   \t\t trailing   ").collect();
+
+    assert_eq!(decode(&encode_with(&input, &BINARY)).unwrap(), input);
+
+    let text = "lines  \nwith\ttabs\t\nand = signs, and a very long one that goes on for well over the seventy six columns\n";
+    assert_eq!(decode(&encode(text.as_bytes())).unwrap(), text.as_bytes());
+}
+
+#[test]
+fn decoding_is_lenient_about_transit_damage() {
+    // whitespace added at the end of lines and lowercase hex
+    assert_eq!(decode("a=3d \nb =\t\nc").unwrap(), b"a=\nb c");
+    assert_eq!(decode("crlf\r\nlines").unwrap(), b"crlf\nlines");
+
+    for input in ["=4", "=G0", "a=\u{e9}", "=4\nb"] {
+        assert!(decode(input).is_err(), "{input}");
+    }
+}
@@ -0,0 +1,46 @@
+#![cfg(feature = "serde")]
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Key {
+    #[serde(with = "base64::serde::base64")]
+    public: Vec<u8>,
+    #[serde(with = "base64::serde::base64_url_no_pad")]
+    token: Vec<u8>,
+    #[serde(with = "base64::serde::hex")]
+    fingerprint: [u8; 4],
+}
+
+fn key() -> Key {
+    Key {
+        public: b"\xfb\xff".to_vec(),
+        token: b"\xfb\xff".to_vec(),
+        fingerprint: [0xde, 0xad, 0xbe, 0xef],
+    }
+}
+
+const JSON: &str = r#"{"public":"+/8=","token":"-_8","fingerprint":"deadbeef"}"#;
+
+#[test]
+fn fields_are_strings() {
+    assert_eq!(serde_json::to_string(&key()).unwrap(), JSON);
+    assert_eq!(serde_json::from_str::<Key>(JSON).unwrap(), key());
+
+    let upper = JSON.replace("deadbeef", "DEADBEEF");
+    assert_eq!(serde_json::from_str::<Key>(&upper).unwrap(), key());
+}
+
+#[test]
+fn bad_fields_are_errors() {
+    for json in [
+        // invalid base64, a url-safe symbol in the standard field
+        JSON.replace("+/8=", "-_8="),
+        // a fingerprint that doesn't fit the array
+        JSON.replace("deadbeef", "deadbeefff"),
+        JSON.replace("deadbeef", "xx"),
+        JSON.replace("\"deadbeef\"", "[222, 173, 190, 239]"),
+    ] {
+        assert!(serde_json::from_str::<Key>(&json).is_err(), "{json}");
+    }
+}
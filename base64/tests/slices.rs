@@ -0,0 +1,63 @@
+use base64::base64::{
+    decode_in_place_with, decode_slice_with, decoded_len_estimate, encode_slice_with, encode_with,
+    encoded_len, Config, STANDARD, STANDARD_NO_PAD, URL_SAFE, URL_SAFE_NO_PAD,
+};
+
+const CONFIGS: [Config; 4] = [STANDARD, STANDARD_NO_PAD, URL_SAFE, URL_SAFE_NO_PAD];
+
+#[test]
+fn rfc_4648_vectors() {
+    let vectors = [
+        ("", ""),
+        ("f", "Zg=="),
+        ("fo", "Zm8="),
+        ("foo", "Zm9v"),
+        ("foob", "Zm9vYg=="),
+        ("fooba", "Zm9vYmE="),
+        ("foobar", "Zm9vYmFy"),
+    ];
+
+    for (input, expected) in vectors {
+        let mut output = [0u8; 8];
+        let len = encode_slice_with(input.as_bytes(), &mut output, &STANDARD).unwrap();
+        assert_eq!(&output[..len], expected.as_bytes());
+
+        let mut decoded = [0u8; 6];
+        let len = decode_slice_with(expected.as_bytes(), &mut decoded, &STANDARD).unwrap();
+        assert_eq!(&decoded[..len], input.as_bytes());
+    }
+}
+
+#[test]
+fn slices_match_the_allocating_api() {
+    let input: Vec<u8> = (0..=255).collect();
+
+    for config in &CONFIGS {
+        for len in 0..input.len() {
+            let expected = encode_with(&input[..len], config);
+
+            let mut encoded = vec![0; encoded_len(len, config.padding).unwrap()];
+            assert_eq!(
+                encode_slice_with(&input[..len], &mut encoded, config).unwrap(),
+                expected.len()
+            );
+            assert_eq!(encoded, expected.as_bytes());
+
+            let mut decoded = vec![0; decoded_len_estimate(encoded.len())];
+            let written = decode_slice_with(&encoded, &mut decoded, config).unwrap();
+            assert_eq!(&decoded[..written], &input[..len]);
+
+            let written = decode_in_place_with(&mut encoded, config).unwrap();
+            assert_eq!(&encoded[..written], &input[..len]);
+        }
+    }
+}
+
+#[test]
+fn small_buffers_are_errors() {
+    let mut output = [0u8; 7];
+    assert!(encode_slice_with(b"foobar", &mut output, &STANDARD).is_err());
+
+    let mut output = [0u8; 5];
+    assert!(decode_slice_with(b"Zm9vYmFy", &mut output, &STANDARD).is_err());
+}
@@ -0,0 +1,79 @@
+use base64::uu::{
+    decode, decode_with, encode, encode_with, UuFile, UU_BACKTICK_ALPHABET, XX_ALPHABET,
+};
+
+#[test]
+fn framing() {
+    let file = UuFile::new("cat.txt", b"Cat".to_vec());
+
+    assert_eq!(encode(&file), "begin 644 cat.txt\n#0V%T\n \nend\n");
+    assert_eq!(
+        encode_with(
+            &UuFile::new("hey.txt", b"hey\n".to_vec()),
+            &UU_BACKTICK_ALPHABET
+        ),
+        "begin 644 hey.txt\n$:&5Y\"@``\n`\nend\n"
+    );
+}
+
+#[test]
+fn lines_hold_45_bytes() {
+    let file = UuFile::new("data", vec![b'x'; 100]);
+    let encoded = encode(&file);
+    let lengths: Vec<char> = encoded
+        .lines()
+        .skip(1)
+        .map(|line| line.chars().next().unwrap_or(' '))
+        .collect();
+
+    // 45 + 45 + 10, and the empty line
+    assert_eq!(lengths, ['M', 'M', '*', ' ', 'e']);
+    assert_eq!(decode(&encoded).unwrap(), file);
+}
+
+#[test]
+fn round_trips() {
+    let contents: Vec<u8> = (0..=255).collect();
+
+    for len in [0, 1, 2, 3, 44, 45, 46, 256] {
+        let mut file = UuFile::new("bytes.bin", contents[..len].to_vec());
+        file.mode = 0o755;
+
+        for alphabet in [&UU_BACKTICK_ALPHABET, &XX_ALPHABET] {
+            assert_eq!(
+                decode_with(&encode_with(&file, alphabet), alphabet).unwrap(),
+                file
+            );
+        }
+        assert_eq!(decode(&encode(&file)).unwrap(), file);
+    }
+}
+
+#[test]
+fn text_around_the_file_and_stripped_spaces() {
+    let input = "From: someone\nSubject: cat\n\nbegin 600 cat.txt\n#0V%T\n\nend\n-- \nsignature\n";
+    let file = decode(input).unwrap();
+
+    assert_eq!(file.mode, 0o600);
+    assert_eq!(file.name, "cat.txt");
+    assert_eq!(file.contents, b"Cat");
+
+    // trailing spaces eaten in transit
+    let file = UuFile::new("zeros", vec![0; 3]);
+    let encoded = encode(&file).replace("    \n", "\n");
+    assert_eq!(decode(&encoded).unwrap(), file);
+}
+
+#[test]
+fn broken_framing_is_an_error() {
+    for input in [
+        "#0V%T\n \nend\n",
+        "begin 644\n#0V%T\n \nend\n",
+        "begin 9x9 cat.txt\n#0V%T\n \nend\n",
+        "begin 644 cat.txt\n#0V%T\n",
+        "begin 644 cat.txt\n#0V%T\n \n",
+        "begin 644 cat.txt\n~0V%T\n \nend\n",
+    ] {
+        assert!(decode(input).is_err(), "{input:?}");
+    }
+}
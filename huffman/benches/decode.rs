@@ -0,0 +1,55 @@
+#[path = "../tests/common/mod.rs"]
+mod common;
+
+use common::skewed_bytes;
+use huffman::{canonical, compress_with, read_header, table::DecodeTable, tree, Config};
+use std::{hint::black_box, time::Instant};
+
+const SIZE: usize = 4 * 1024 * 1024;
+const ROUNDS: u32 = 5;
+
+fn throughput<T>(name: &str, bytes: usize, mut f: impl FnMut() -> T) -> f64 {
+    // warm up the caches and the allocator
+    black_box(f());
+
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        black_box(f());
+    }
+    let elapsed = start.elapsed();
+
+    let mb_per_s = (bytes as f64 * ROUNDS as f64) / (1024.0 * 1024.0) / elapsed.as_secs_f64();
+    println!(
+        "{name:<10} {mb_per_s:>10.1} MB/s ({:?} per {} MiB)",
+        elapsed / ROUNDS,
+        bytes / (1024 * 1024)
+    );
+
+    mb_per_s
+}
+
+fn main() {
+    let input = skewed_bytes(SIZE, 0x2545_F491_4F6C_DD1D);
+    // one block, so it's all a single table
+    let config = Config::DEFAULT.with_block_size(u64::MAX);
+    let compressed = compress_with(&input, &config).unwrap();
+
+    let header = read_header(&compressed).unwrap();
+    let block = &header.blocks[0];
+    let data = &compressed[block.data.clone()];
+
+    let tree = canonical::build_tree(&block.lengths).unwrap();
+    let table = DecodeTable::new(&block.lengths).unwrap();
+
+    assert_eq!(tree::decode(data, &tree, input.len()), input);
+    assert_eq!(table.decode(data, input.len()).unwrap(), input);
+
+    let tree_walk = throughput("tree walk", input.len(), || {
+        tree::decode(black_box(data), &tree, input.len())
+    });
+    let table_driven = throughput("table", input.len(), || {
+        table.decode(black_box(data), input.len()).unwrap()
+    });
+
+    println!("{:.1}x faster", table_driven / tree_walk);
+}
@@ -0,0 +1,375 @@
+use crate::bits::BitWriter;
+use crate::crc32::Crc32;
+use crate::error::Error;
+use std::io::{self, BufRead, BufReader, Read, Write};
+
+/*
+* one pass adaptive huffman (FGK), the encoder and the decoder start from the same
+* empty tree and update it the same way after every symbol, so the tree never has to
+* be stored and nothing has to be read ahead
+*
+* the tree starts as a single NYT (not yet transmitted) leaf of weight 0. a symbol
+* seen before is sent as its code, a new one as the NYT's code followed by its 9 raw
+* bits, and then the NYT splits into a new NYT and a leaf for the symbol:
+*
+*       NYT    ->     *
+*                    / \
+*                  NYT  'a'
+*
+* the nodes are numbered so the weights never decrease with the number (the sibling
+* property), and a node is numbered by its slot in `nodes`. before a node's weight goes
+* up it swaps with the highest numbered node of the same weight, which keeps that true
+*
+* the symbols are the 256 bytes and an end symbol, since a stream's length isn't known
+* up front. after the end symbol's byte comes the CRC-32 of the data, u32 little endian
+*/
+
+const END: u16 = 256;
+const SYMBOLS: usize = 257;
+// every symbol and the NYT as leaves
+const MAX_NODES: usize = 2 * (SYMBOLS + 1) - 1;
+const ROOT: usize = MAX_NODES - 1;
+const NONE: usize = usize::MAX;
+const RAW_BITS: u32 = 9;
+
+#[derive(Clone, Copy)]
+struct Node {
+    weight: u64,
+    parent: usize,
+    // both are `NONE` for the leaves
+    left: usize,
+    right: usize,
+    // `None` for the internal nodes and the NYT
+    symbol: Option<u16>,
+}
+
+const EMPTY: Node = Node {
+    weight: 0,
+    parent: NONE,
+    left: NONE,
+    right: NONE,
+    symbol: None,
+};
+
+struct Tree {
+    nodes: [Node; MAX_NODES],
+    leaves: [usize; SYMBOLS],
+    nyt: usize,
+}
+
+impl Tree {
+    fn new() -> Self {
+        Tree {
+            nodes: [EMPTY; MAX_NODES],
+            leaves: [NONE; SYMBOLS],
+            nyt: ROOT,
+        }
+    }
+
+    fn is_leaf(&self, node: usize) -> bool {
+        self.nodes[node].left == NONE
+    }
+
+    // the path from the root down to `node`
+    fn code(&self, mut node: usize, code: &mut Vec<bool>) {
+        code.clear();
+
+        while node != ROOT {
+            let parent = self.nodes[node].parent;
+            code.push(self.nodes[parent].right == node);
+            node = parent;
+        }
+
+        code.reverse();
+    }
+
+    // the highest numbered node with the same weight, the weights never decrease with
+    // the number so they're all right above it
+    fn leader(&self, node: usize) -> usize {
+        let weight = self.nodes[node].weight;
+        let mut leader = node;
+
+        while leader < ROOT && self.nodes[leader + 1].weight == weight {
+            leader += 1;
+        }
+
+        leader
+    }
+
+    // swaps the subtrees at the two slots, the parents stay where they are
+    fn swap(&mut self, a: usize, b: usize) {
+        let (parent_a, parent_b) = (self.nodes[a].parent, self.nodes[b].parent);
+        self.nodes.swap(a, b);
+        self.nodes[a].parent = parent_a;
+        self.nodes[b].parent = parent_b;
+
+        for slot in [a, b] {
+            let node = self.nodes[slot];
+
+            match node.symbol {
+                _ if !self.is_leaf(slot) => {
+                    self.nodes[node.left].parent = slot;
+                    self.nodes[node.right].parent = slot;
+                }
+                Some(symbol) => self.leaves[symbol as usize] = slot,
+                None => self.nyt = slot,
+            }
+        }
+    }
+
+    fn update(&mut self, symbol: u16) {
+        let mut node = match self.leaves[symbol as usize] {
+            NONE => {
+                let parent = self.nyt;
+                let (nyt, leaf) = (parent - 2, parent - 1);
+
+                self.nodes[nyt] = Node { parent, ..EMPTY };
+                self.nodes[leaf] = Node {
+                    parent,
+                    symbol: Some(symbol),
+                    ..EMPTY
+                };
+                self.nodes[parent].left = nyt;
+                self.nodes[parent].right = leaf;
+
+                self.nyt = nyt;
+                self.leaves[symbol as usize] = leaf;
+                leaf
+            }
+            leaf => leaf,
+        };
+
+        loop {
+            let leader = self.leader(node);
+            if leader != node && leader != self.nodes[node].parent {
+                self.swap(node, leader);
+                node = leader;
+            }
+
+            self.nodes[node].weight += 1;
+
+            if node == ROOT {
+                break;
+            }
+            node = self.nodes[node].parent;
+        }
+    }
+
+    fn encode(&mut self, symbol: u16, code: &mut Vec<bool>, bits: &mut BitWriter) {
+        match self.leaves[symbol as usize] {
+            NONE => {
+                self.code(self.nyt, code);
+                bits.write_bits(code);
+                for i in (0..RAW_BITS).rev() {
+                    bits.write_bit(symbol >> i & 1 == 1);
+                }
+            }
+            leaf => {
+                self.code(leaf, code);
+                bits.write_bits(code);
+            }
+        }
+
+        self.update(symbol);
+    }
+
+    fn decode<R: Read>(&mut self, bits: &mut Bits<R>) -> io::Result<u16> {
+        let mut node = ROOT;
+        while !self.is_leaf(node) {
+            node = match bits.read_bit()? {
+                true => self.nodes[node].right,
+                false => self.nodes[node].left,
+            };
+        }
+
+        let symbol = match self.nodes[node].symbol {
+            Some(symbol) => symbol,
+            None => {
+                let mut symbol = 0;
+                for _ in 0..RAW_BITS {
+                    symbol = symbol << 1 | bits.read_bit()? as u16;
+                }
+
+                if symbol as usize >= SYMBOLS || self.leaves[symbol as usize] != NONE {
+                    return Err(Error::Corrupted(format!(
+                        "invalid new symbol {symbol} at byte {}",
+                        bits.read
+                    ))
+                    .into());
+                }
+                symbol
+            }
+        };
+
+        self.update(symbol);
+        Ok(symbol)
+    }
+}
+
+pub struct Encoder<W: Write> {
+    tree: Tree,
+    output: W,
+    bits: BitWriter,
+    code: Vec<bool>,
+    crc: Crc32,
+}
+
+impl<W: Write> Encoder<W> {
+    pub fn new(output: W) -> Self {
+        Encoder {
+            tree: Tree::new(),
+            output,
+            bits: BitWriter::new(),
+            code: Vec::new(),
+            crc: Crc32::new(),
+        }
+    }
+
+    // writes the end symbol, the last partial byte and the checksum, and hands the
+    // output back
+    pub fn finish(mut self) -> io::Result<W> {
+        self.tree.encode(END, &mut self.code, &mut self.bits);
+        self.bits.flush();
+        self.output.write_all(&self.bits.take_bytes())?;
+        self.output.write_all(&self.crc.finish().to_le_bytes())?;
+
+        Ok(self.output)
+    }
+}
+
+impl<W: Write> Write for Encoder<W> {
+    fn write(&mut self, input: &[u8]) -> io::Result<usize> {
+        for &byte in input {
+            self.tree
+                .encode(byte as u16, &mut self.code, &mut self.bits);
+        }
+
+        self.crc.update(input);
+        self.output.write_all(&self.bits.take_bytes())?;
+        Ok(input.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.output.flush()
+    }
+}
+
+// the input one bit at a time, most significant bit first
+struct Bits<R: Read> {
+    input: BufReader<R>,
+    byte: u8,
+    left: u8,
+    read: u64,
+}
+
+impl<R: Read> Bits<R> {
+    fn read_bytes<const N: usize>(&mut self, what: &str) -> io::Result<[u8; N]> {
+        let mut bytes = [0; N];
+        self.input
+            .read_exact(&mut bytes)
+            .map_err(|e| match e.kind() {
+                io::ErrorKind::UnexpectedEof => Error::Truncated(format!(
+                    "the data ended after {} bytes, before the {what}",
+                    self.read
+                ))
+                .into(),
+                _ => e,
+            })?;
+
+        self.read += N as u64;
+        Ok(bytes)
+    }
+
+    fn read_bit(&mut self) -> io::Result<bool> {
+        if self.left == 0 {
+            [self.byte] = self.read_bytes("end symbol")?;
+            self.left = 8;
+        }
+
+        self.left -= 1;
+        Ok(self.byte >> self.left & 1 == 1)
+    }
+}
+
+// reads the decoded bytes, up to the end symbol
+pub struct Decoder<R: Read> {
+    tree: Tree,
+    bits: Bits<R>,
+    crc: Crc32,
+    done: bool,
+}
+
+impl<R: Read> Decoder<R> {
+    pub fn new(input: R) -> Self {
+        Decoder {
+            tree: Tree::new(),
+            bits: Bits {
+                input: BufReader::new(input),
+                byte: 0,
+                left: 0,
+                read: 0,
+            },
+            crc: Crc32::new(),
+            done: false,
+        }
+    }
+
+    // the rest of the end symbol's byte is padding, and the checksum has to be the last
+    // thing in the input
+    fn finish(&mut self) -> io::Result<()> {
+        let expected = u32::from_le_bytes(self.bits.read_bytes("checksum")?);
+        let actual = self.crc.finish();
+
+        if expected != actual {
+            return Err(Error::Checksum { expected, actual }.into());
+        }
+
+        let trailing = self.bits.input.fill_buf()?.len();
+        if trailing > 0 {
+            return Err(
+                Error::Malformed(format!("{trailing} or more bytes after the checksum")).into(),
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for Decoder<R> {
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        let mut len = 0;
+        let mut ended = false;
+
+        while len < buffer.len() && !self.done {
+            match self.tree.decode(&mut self.bits)? {
+                END => (self.done, ended) = (true, true),
+                symbol => {
+                    buffer[len] = symbol as u8;
+                    len += 1;
+                }
+            }
+        }
+
+        self.crc.update(&buffer[..len]);
+        if ended {
+            self.finish()?;
+        }
+
+        Ok(len)
+    }
+}
+
+pub fn encode(input: &[u8]) -> Vec<u8> {
+    let mut encoder = Encoder::new(Vec::new());
+    encoder
+        .write_all(input)
+        .expect("writing to a vec can't fail");
+    encoder.finish().expect("writing to a vec can't fail")
+}
+
+pub fn decode(encoded: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut output = Vec::new();
+    Decoder::new(encoded).read_to_end(&mut output)?;
+
+    Ok(output)
+}
@@ -0,0 +1,73 @@
+#[derive(Default)]
+pub struct BitWriter {
+    bits: Vec<u8>,
+    current_byte: u8,
+    bit_count: u8,
+}
+
+impl BitWriter {
+    pub fn new() -> Self {
+        BitWriter {
+            bits: Vec::new(),
+            current_byte: 0,
+            bit_count: 0,
+        }
+    }
+    pub fn write_bit(&mut self, bit: bool) {
+        if bit {
+            self.current_byte |= 1 << (7 - self.bit_count);
+        }
+        self.bit_count += 1;
+        if self.bit_count == 8 {
+            self.bits.push(self.current_byte);
+            self.current_byte = 0;
+            self.bit_count = 0;
+        }
+    }
+    pub fn write_bits(&mut self, bits: &[bool]) {
+        for &bit in bits {
+            self.write_bit(bit);
+        }
+    }
+    pub fn flush(&mut self) {
+        if self.bit_count > 0 {
+            self.bits.push(self.current_byte);
+            self.bit_count = 0;
+        }
+    }
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bits
+    }
+    // the whole bytes written so far, a partial byte stays behind
+    pub fn take_bytes(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.bits)
+    }
+}
+
+pub struct BitReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    bit_pos: u8,
+}
+
+impl<'a> BitReader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        BitReader {
+            bytes,
+            pos: 0,
+            bit_pos: 0,
+        }
+    }
+    pub fn read_bit(&mut self) -> Option<bool> {
+        if self.pos >= self.bytes.len() {
+            return None;
+        }
+        let bit = (self.bytes[self.pos] & (1 << (7 - self.bit_pos))) != 0;
+        self.bit_pos += 1;
+        if self.bit_pos == 8 {
+            self.pos += 1;
+            self.bit_pos = 0;
+        }
+        Some(bit)
+    }
+}
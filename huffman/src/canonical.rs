@@ -0,0 +1,287 @@
+use anyhow::bail;
+use std::collections::HashMap;
+
+use crate::error::Error;
+use crate::tree::{generate_codes, Node};
+
+/*
+* canonical huffman codes, only the code length of every symbol is stored, and the
+* codes are handed out in (length, symbol) order, counting up and shifting left
+* whenever the length grows:
+*
+*   symbol  length  code
+*   b       1       0
+*   a       2       10
+*   c       3       110
+*   d       3       111
+*
+* any tree with the same lengths compresses just as well, and the codes don't depend
+* on how the tree happened to be built, so the output is the same on every machine
+*
+* a length of 0 means the symbol doesn't occur
+*/
+
+pub type Lengths = [u8; 256];
+
+// the longest code a file can have. a code always fits in what's left of the table
+// decoder's 64 bit buffer after a refill, and its secondary tables stay at most 2^10
+// entries each. longer codes would let a crafted table make the decoder fill megabytes
+// of secondary tables for every block
+pub const MAX_CODE_LEN: u8 = 20;
+
+const BITMAP_LEN: usize = 256 / 8;
+
+pub fn code_lengths(root: &Node) -> Lengths {
+    let mut lengths = [0; 256];
+
+    for (symbol, code) in generate_codes(root) {
+        lengths[symbol as usize] = code.len() as u8;
+    }
+
+    lengths
+}
+
+/*
+* package-merge, the optimal code lengths that are at most `max_len`, for when the
+* tree's are too long (a fibonacci-like distribution makes a tree as deep as there
+* are symbols)
+*
+* a code of length l is an item at each of the levels 1 to l, each worth a 2^-level
+* share of the kraft sum. starting at the deepest level, the cheapest items are paired
+* up into packages and merged into the next level's leaves, and at level 1 the cheapest
+* 2n - 2 items are picked, every leaf in them adds one to its symbol's length
+*/
+pub fn limited_code_lengths(freq_map: &HashMap<u8, u64>, max_len: u8) -> anyhow::Result<Lengths> {
+    let mut leaves: Vec<(u8, u64)> = freq_map.iter().map(|(&symbol, &f)| (symbol, f)).collect();
+    leaves.sort_unstable_by_key(|&(symbol, frequency)| (frequency, symbol));
+
+    let n = leaves.len();
+    let mut lengths = [0; 256];
+
+    if let [(symbol, _)] = leaves[..] {
+        lengths[symbol as usize] = 1;
+    }
+
+    if n < 2 {
+        return Ok(lengths);
+    }
+
+    if max_len == 0 || max_len > MAX_CODE_LEN || (n - 1) >> max_len != 0 {
+        bail!("{n} symbols don't fit in codes of at most {max_len} bits");
+    }
+
+    // an item is its weight and how many times it holds each leaf
+    let leaf_items: Vec<(u64, Vec<u8>)> = leaves
+        .iter()
+        .enumerate()
+        .map(|(i, &(_, frequency))| {
+            let mut counts = vec![0; n];
+            counts[i] = 1;
+            (frequency, counts)
+        })
+        .collect();
+
+    let mut items = leaf_items.clone();
+
+    for _ in 1..max_len {
+        let packages = items.chunks_exact(2).map(|pair| {
+            let counts = pair[0]
+                .1
+                .iter()
+                .zip(&pair[1].1)
+                .map(|(a, b)| a + b)
+                .collect();
+            (pair[0].0 + pair[1].0, counts)
+        });
+
+        // a stable merge, leaves go before packages of the same weight
+        let mut merged: Vec<(u64, Vec<u8>)> = leaf_items.iter().cloned().chain(packages).collect();
+        merged.sort_by_key(|(weight, _)| *weight);
+        items = merged;
+    }
+
+    for (_, counts) in &items[..2 * n - 2] {
+        for (i, count) in counts.iter().enumerate() {
+            lengths[leaves[i].0 as usize] += count;
+        }
+    }
+
+    Ok(lengths)
+}
+
+// the symbols that occur with their code, in canonical order
+pub(crate) fn assign(lengths: &Lengths) -> Vec<(u8, u64, u8)> {
+    let mut symbols: Vec<(u8, u8)> = (0..=255u8)
+        .filter(|&symbol| lengths[symbol as usize] != 0)
+        .map(|symbol| (lengths[symbol as usize], symbol))
+        .collect();
+    symbols.sort_unstable();
+
+    let mut code = 0u64;
+    let mut previous_len = symbols.first().map_or(0, |&(len, _)| len);
+
+    symbols
+        .into_iter()
+        .map(|(len, symbol)| {
+            code <<= len - previous_len;
+            previous_len = len;
+
+            let assigned = (symbol, code, len);
+            code = code.wrapping_add(1);
+            assigned
+        })
+        .collect()
+}
+
+pub fn codes(lengths: &Lengths) -> HashMap<u8, Vec<bool>> {
+    assign(lengths)
+        .into_iter()
+        .map(|(symbol, code, len)| {
+            let bits = (0..len).rev().map(|i| code >> i & 1 == 1).collect();
+            (symbol, bits)
+        })
+        .collect()
+}
+
+// the lengths have to describe a full tree, every node having both children, which is
+// what the kraft sum of exactly 1 means. the exceptions are no symbols at all (an empty
+// input), and a single symbol, which gets the code "0"
+pub fn validate(lengths: &Lengths) -> Result<(), Error> {
+    let malformed = |message: String| Err(Error::Malformed(message));
+
+    if let Some(len) = lengths.iter().find(|&&len| len > MAX_CODE_LEN) {
+        return malformed(format!(
+            "a code length of {len} is over the maximum of {MAX_CODE_LEN}"
+        ));
+    }
+
+    let mut used = lengths.iter().filter(|&&len| len != 0);
+    match (used.next(), used.next()) {
+        (None, _) => return Ok(()),
+        (Some(1), None) => return Ok(()),
+        (Some(len), None) => {
+            return malformed(format!("a lone symbol's code has to be 1 bit, not {len}"))
+        }
+        _ => {}
+    }
+
+    let kraft: u128 = lengths
+        .iter()
+        .filter(|&&len| len != 0)
+        .map(|&len| 1u128 << (MAX_CODE_LEN - len))
+        .sum();
+
+    match kraft.cmp(&(1 << MAX_CODE_LEN)) {
+        std::cmp::Ordering::Less => malformed("the code lengths leave some codes unused".into()),
+        std::cmp::Ordering::Greater => {
+            malformed("the code lengths have more codes than fit".into())
+        }
+        std::cmp::Ordering::Equal => Ok(()),
+    }
+}
+
+// the tree the codes describe, for the decoder
+pub fn build_tree(lengths: &Lengths) -> Result<Node, Error> {
+    validate(lengths)?;
+
+    let codes = assign(lengths);
+
+    // like `build_huffman_tree`, a lone symbol is the root itself
+    match codes[..] {
+        [] => {
+            return Err(Error::Malformed(
+                "there are no codes to build a tree from".into(),
+            ))
+        }
+        [(symbol, _, _)] => {
+            return Ok(Node::Leaf {
+                symbol,
+                frequency: 0,
+            })
+        }
+        _ => {}
+    }
+
+    // canonical order is also the order of the codes read as bit strings, so every
+    // subtree is a contiguous run, split by the next bit. `validate` already keeps the
+    // depth to the longest code, the checks are there so the recursion can't run away
+    // if that ever changes
+    fn build(codes: &[(u8, u64, u8)], depth: u8) -> Result<Node, Error> {
+        match codes {
+            [(symbol, _, len)] if *len == depth => Ok(Node::Leaf {
+                symbol: *symbol,
+                frequency: 0,
+            }),
+            [] => Err(Error::Malformed(format!(
+                "a node at depth {depth} has no codes under it"
+            ))),
+            _ if depth >= MAX_CODE_LEN => Err(Error::Malformed(format!(
+                "the tree is deeper than {MAX_CODE_LEN}"
+            ))),
+            _ => {
+                let split =
+                    codes.partition_point(|&(_, code, len)| code >> (len - depth - 1) & 1 == 0);
+
+                Ok(Node::Internal {
+                    frequency: 0,
+                    left: Box::new(build(&codes[..split], depth + 1)?),
+                    right: Box::new(build(&codes[split..], depth + 1)?),
+                })
+            }
+        }
+    }
+
+    build(&codes, 0)
+}
+
+// a bitmap of the symbols that occur, bit `symbol % 8` of byte `symbol / 8`, and then
+// the length of each of them in symbol order
+pub fn write_lengths(lengths: &Lengths, output: &mut Vec<u8>) {
+    let mut bitmap = [0u8; BITMAP_LEN];
+
+    for (symbol, _) in lengths.iter().enumerate().filter(|(_, &len)| len != 0) {
+        bitmap[symbol / 8] |= 1 << (symbol % 8);
+    }
+
+    output.extend_from_slice(&bitmap);
+    output.extend(lengths.iter().filter(|&&len| len != 0));
+}
+
+// returns the lengths and how many bytes they took
+pub fn read_lengths(bytes: &[u8]) -> Result<(Lengths, usize), Error> {
+    let Some((bitmap, rest)) = bytes.split_first_chunk::<BITMAP_LEN>() else {
+        return Err(Error::Truncated(format!(
+            "the {BITMAP_LEN} byte bitmap of the code lengths is cut off at {} bytes",
+            bytes.len()
+        )));
+    };
+
+    let count = bitmap
+        .iter()
+        .map(|b| b.count_ones() as usize)
+        .sum::<usize>();
+    let Some(stored) = rest.get(..count) else {
+        return Err(Error::Truncated(format!(
+            "the code lengths are cut off, {count} symbols but only {} lengths",
+            rest.len()
+        )));
+    };
+
+    let mut lengths = [0; 256];
+    let mut stored = stored.iter();
+
+    for (symbol, length) in lengths.iter_mut().enumerate() {
+        if bitmap[symbol / 8] >> (symbol % 8) & 1 == 1 {
+            *length = *stored.next().unwrap();
+
+            // 0 is how an absent symbol is kept in `Lengths`
+            if *length == 0 {
+                return Err(Error::Malformed(format!(
+                    "the symbol {symbol} is in the bitmap with a code length of 0"
+                )));
+            }
+        }
+    }
+
+    Ok((lengths, BITMAP_LEN + count))
+}
@@ -0,0 +1,62 @@
+/*
+* CRC-32 the way zip, gzip and png compute it, the reflected 0x04C11DB7 polynomial
+* starting from and xored with all ones, a byte at a time through a table of what
+* every byte value does to the remainder
+*/
+
+const POLYNOMIAL: u32 = 0xEDB88320;
+
+const TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                crc >> 1 ^ POLYNOMIAL
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+
+        table[i] = crc;
+        i += 1;
+    }
+
+    table
+};
+
+// for data that comes in pieces
+#[derive(Debug, Clone, Copy)]
+pub struct Crc32(u32);
+
+impl Crc32 {
+    pub const fn new() -> Self {
+        Crc32(!0)
+    }
+
+    pub fn update(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = self.0 >> 8 ^ TABLE[(self.0 as u8 ^ byte) as usize];
+        }
+    }
+
+    pub const fn finish(self) -> u32 {
+        !self.0
+    }
+}
+
+impl Default for Crc32 {
+    fn default() -> Self {
+        Crc32::new()
+    }
+}
+
+pub fn checksum(bytes: &[u8]) -> u32 {
+    let mut crc = Crc32::new();
+    crc.update(bytes);
+    crc.finish()
+}
@@ -0,0 +1,98 @@
+use std::{fmt, io};
+
+/*
+* what can go wrong reading a .huf file, so a caller can tell a file that got cut off
+* from one that's corrupted or isn't a .huf file at all. writing one only fails on bad
+* settings, and that side stays on anyhow
+*/
+
+#[derive(Debug)]
+pub enum Error {
+    // it doesn't start with the magic
+    NotHuf,
+    UnknownMode(u8),
+    // the file ends before something the header says is there
+    Truncated(String),
+    // a header or code lengths that no encoder writes
+    Malformed(String),
+    // the data has a code that isn't in the table
+    Corrupted(String),
+    // everything decoded, but not into what was compressed
+    Checksum { expected: u32, actual: u32 },
+    // compressed with a custom table, and it wasn't given or it's a different one
+    CustomTable { expected: u32, given: Option<u32> },
+    Io(io::Error),
+}
+
+impl Error {
+    // says which block of a canonical file the message is about
+    pub(crate) fn in_block(self, block: usize) -> Self {
+        match self {
+            Error::Truncated(message) => Error::Truncated(format!("block {block}, {message}")),
+            Error::Malformed(message) => Error::Malformed(format!("block {block}, {message}")),
+            Error::Corrupted(message) => Error::Corrupted(format!("block {block}, {message}")),
+            e => e,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::NotHuf => write!(f, "not a .huf file, it doesn't start with \"HUF\""),
+            Error::UnknownMode(mode) => write!(f, "unknown mode {mode}"),
+            Error::Truncated(message) => write!(f, "truncated file: {message}"),
+            Error::Malformed(message) => write!(f, "malformed file: {message}"),
+            Error::Corrupted(message) => write!(f, "corrupted data: {message}"),
+            Error::Checksum { expected, actual } => write!(
+                f,
+                "checksum mismatch, the file has {expected:08x} but the data is {actual:08x}"
+            ),
+            Error::CustomTable {
+                expected,
+                given: None,
+            } => write!(
+                f,
+                "the file was compressed with a custom table ({expected:08x}), it has to be given to decompress it"
+            ),
+            Error::CustomTable {
+                expected,
+                given: Some(given),
+            } => write!(
+                f,
+                "the file was compressed with the custom table {expected:08x}, not {given:08x}"
+            ),
+            Error::Io(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+// the adaptive decoder is a `Read`, so its errors come wrapped in an io::Error
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        if e.get_ref().is_some_and(|inner| inner.is::<Error>()) {
+            return *e.into_inner().unwrap().downcast::<Error>().unwrap();
+        }
+
+        Error::Io(e)
+    }
+}
+
+impl From<Error> for io::Error {
+    fn from(e: Error) -> Self {
+        match e {
+            Error::Io(e) => e,
+            Error::Truncated(_) => io::Error::new(io::ErrorKind::UnexpectedEof, e),
+            e => io::Error::new(io::ErrorKind::InvalidData, e),
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::tree::{generate_codes, Node};
+
+/*
+* what the codes look like, for tuning: every symbol's frequency and code, and how the
+* average code length compares to the entropy, the least bits per symbol any code can
+* average. huffman is within a bit of it, and closer the less skewed the data is
+*
+* the tree can be written as a Graphviz DOT file:
+*
+*   dot -Tsvg tree.dot -o tree.svg
+*/
+
+#[derive(Debug, Clone)]
+pub struct SymbolCode {
+    pub symbol: u8,
+    pub frequency: u64,
+    pub code: Vec<bool>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Report {
+    // the most frequent first
+    pub symbols: Vec<SymbolCode>,
+    pub total: u64,
+    // both in bits per symbol
+    pub average_len: f64,
+    pub entropy: f64,
+}
+
+// only the symbols that occur, a built-in table has codes for every byte
+pub fn report(root: &Node, freq_map: &HashMap<u8, u64>) -> Report {
+    let mut symbols: Vec<SymbolCode> = generate_codes(root)
+        .into_iter()
+        .filter_map(|(symbol, code)| {
+            Some(SymbolCode {
+                symbol,
+                frequency: *freq_map.get(&symbol).filter(|&&frequency| frequency > 0)?,
+                code,
+            })
+        })
+        .collect();
+    symbols.sort_unstable_by_key(|s| (std::cmp::Reverse(s.frequency), s.symbol));
+
+    let total: u64 = symbols.iter().map(|s| s.frequency).sum();
+    let probability = |s: &SymbolCode| s.frequency as f64 / total.max(1) as f64;
+
+    Report {
+        average_len: symbols
+            .iter()
+            .map(|s| probability(s) * s.code.len() as f64)
+            .sum(),
+        entropy: symbols
+            .iter()
+            .map(probability)
+            .filter(|&p| p > 0.0)
+            .map(|p| -p * p.log2())
+            .sum(),
+        symbols,
+        total,
+    }
+}
+
+// a tree rebuilt from code lengths has no frequencies, this fills them back in
+pub fn weigh(node: &Node, freq_map: &HashMap<u8, u64>) -> Node {
+    match node {
+        Node::Leaf { symbol, .. } => Node::Leaf {
+            symbol: *symbol,
+            frequency: freq_map.get(symbol).copied().unwrap_or(0),
+        },
+        Node::Internal { left, right, .. } => {
+            let (left, right) = (weigh(left, freq_map), weigh(right, freq_map));
+
+            Node::Internal {
+                frequency: left.frequency() + right.frequency(),
+                left: Box::new(left),
+                right: Box::new(right),
+            }
+        }
+    }
+}
+
+// printable symbols as themselves, the rest (and space) as hex
+pub fn symbol_name(symbol: u8) -> String {
+    match symbol {
+        b'!'..=b'~' => (symbol as char).to_string(),
+        _ => format!("0x{symbol:02x}"),
+    }
+}
+
+pub fn code_string(code: &[bool]) -> String {
+    code.iter()
+        .map(|&bit| if bit { '1' } else { '0' })
+        .collect()
+}
+
+// leaves are boxes with the symbol and its frequency, internal nodes circles with the
+// frequency under them, and the edges are labeled with their bit
+pub fn to_dot(root: &Node) -> String {
+    fn write_node(node: &Node, id: &mut usize, dot: &mut String) -> usize {
+        let this = *id;
+        *id += 1;
+
+        match node {
+            Node::Leaf { symbol, frequency } => {
+                let name = symbol_name(*symbol)
+                    .replace('\\', "\\\\")
+                    .replace('"', "\\\"");
+                writeln!(
+                    dot,
+                    "    n{this} [shape=box, label=\"{name}\\n{frequency}\"];"
+                )
+                .unwrap();
+            }
+            Node::Internal {
+                frequency,
+                left,
+                right,
+            } => {
+                writeln!(dot, "    n{this} [label=\"{frequency}\"];").unwrap();
+
+                for (bit, child) in [(0, left), (1, right)] {
+                    let child = write_node(child, id, dot);
+                    writeln!(dot, "    n{this} -> n{child} [label=\"{bit}\"];").unwrap();
+                }
+            }
+        }
+
+        this
+    }
+
+    let mut dot = String::from("digraph huffman {\n    node [shape=circle];\n");
+    write_node(root, &mut 0, &mut dot);
+    dot.push_str("}\n");
+
+    dot
+}
@@ -0,0 +1,521 @@
+pub mod adaptive;
+pub mod bits;
+pub mod canonical;
+pub mod crc32;
+pub mod error;
+pub mod inspect;
+pub mod presets;
+pub mod table;
+pub mod tree;
+
+use anyhow::{anyhow, bail};
+use canonical::Lengths;
+pub use error::Error;
+use presets::Preset;
+use std::{
+    collections::HashMap,
+    io::{Read, Write},
+    ops::Range,
+};
+use table::DecodeTable;
+use tree::{build_frequency_map, build_huffman_tree, encode};
+
+/*
+* the .huf format:
+*
+*   magic             "HUF"
+*   mode              a byte, 0 for canonical codes and 1 for adaptive
+*
+* canonical codes are two passes over every block, one to count the symbols and one
+* to encode them, so a file that changes character halfway gets codes for each half:
+*
+*   original length   u64, little endian
+*   block size        u64, little endian, every block but the last is this many bytes
+*   blocks            one after the other until the original length is covered
+*
+* and every block is:
+*
+*   table             a byte for where the codes come from, whichever is the cheapest:
+*                       0  the block's own code lengths follow, a 32 byte bitmap of the
+*                          symbols that occur and then a byte with the code length of
+*                          each of them (see `canonical`)
+*                       1  the previous block's
+*                       2  a built-in table, its id follows in a byte (see `presets`)
+*                       3  a custom table, the u32 little endian checksum of it follows
+*   data length       u64, little endian, the encoded bytes
+*   data              the canonical codes, most significant bit first, zero padded to a byte
+*
+* and after the last block:
+*
+*   checksum          u32, little endian, the CRC-32 of the original data
+*
+* adaptive is a single pass that streams, the data follows the mode right away and ends
+* with an end symbol and the checksum (see `adaptive`)
+*/
+
+const MAGIC: &[u8; 3] = b"HUF";
+const PREFIX_LEN: usize = MAGIC.len() + 1;
+const LENGTH_LEN: usize = 8;
+const CHECKSUM_LEN: usize = 4;
+const STORED_TABLE: u8 = 0;
+const PREVIOUS_TABLE: u8 = 1;
+const PRESET_TABLE: u8 = 2;
+const CUSTOM_TABLE: u8 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Canonical = 0,
+    Adaptive = 1,
+}
+
+impl Mode {
+    fn prefix(self) -> [u8; PREFIX_LEN] {
+        [MAGIC[0], MAGIC[1], MAGIC[2], self as u8]
+    }
+}
+
+pub fn read_mode(bytes: &[u8]) -> Result<Mode, Error> {
+    if !bytes.starts_with(&MAGIC[..bytes.len().min(MAGIC.len())]) {
+        return Err(Error::NotHuf);
+    }
+
+    let Some(prefix) = bytes.first_chunk::<PREFIX_LEN>() else {
+        return Err(Error::Truncated(format!(
+            "the file is {} bytes, too short for the {PREFIX_LEN} byte magic and mode",
+            bytes.len()
+        )));
+    };
+
+    match prefix[MAGIC.len()] {
+        0 => Ok(Mode::Canonical),
+        1 => Ok(Mode::Adaptive),
+        mode => Err(Error::UnknownMode(mode)),
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Config {
+    // longer codes are limited with package-merge, 15 is what DEFLATE allows
+    pub max_code_len: u8,
+    // in bytes of the input, smaller blocks follow changes in the data more closely but
+    // store more tables
+    pub block_size: u64,
+    // whether blocks may use the built-in tables instead of storing their own
+    pub presets: bool,
+    // another table blocks may use, it has to be given again to decompress
+    pub custom_table: Option<Lengths>,
+}
+
+impl Config {
+    pub const DEFAULT: Config = Config {
+        max_code_len: 15,
+        block_size: 1 << 20,
+        presets: true,
+        custom_table: None,
+    };
+
+    pub const fn with_max_code_len(self, max_code_len: u8) -> Self {
+        Config {
+            max_code_len,
+            ..self
+        }
+    }
+
+    pub const fn with_block_size(self, block_size: u64) -> Self {
+        Config { block_size, ..self }
+    }
+
+    pub const fn with_presets(self, presets: bool) -> Self {
+        Config { presets, ..self }
+    }
+
+    pub const fn with_custom_table(self, custom_table: Lengths) -> Self {
+        Config {
+            custom_table: Some(custom_table),
+            ..self
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config::DEFAULT
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableSource {
+    Stored,
+    Previous,
+    Preset(Preset),
+    // the checksum of the table
+    Custom(u32),
+}
+
+#[derive(Debug, Clone)]
+pub struct Block {
+    // the bytes of the input it decodes to
+    pub len: u64,
+    pub lengths: Lengths,
+    pub table: TableSource,
+    // where its encoded data is in the file
+    pub data: Range<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Header {
+    pub original_len: u64,
+    pub block_size: u64,
+    pub blocks: Vec<Block>,
+    pub checksum: u32,
+    // the bytes of the file that aren't data, the block headers included
+    pub len: usize,
+}
+
+fn read_u64(bytes: &[u8], pos: &mut usize, what: &str) -> Result<u64, Error> {
+    let Some(value) = bytes
+        .get(*pos..)
+        .and_then(|rest| rest.first_chunk::<LENGTH_LEN>())
+    else {
+        return Err(Error::Truncated(format!(
+            "the file ended at byte {}, before the {LENGTH_LEN} byte {what}",
+            bytes.len()
+        )));
+    };
+
+    *pos += LENGTH_LEN;
+    Ok(u64::from_le_bytes(*value))
+}
+
+pub fn read_header(bytes: &[u8]) -> Result<Header, Error> {
+    read_header_with(bytes, None)
+}
+
+// only canonical files have one, adaptive ones go straight to the data. every length
+// is checked against the size of the file, so nothing is read or allocated past it
+pub fn read_header_with(bytes: &[u8], custom_table: Option<&Lengths>) -> Result<Header, Error> {
+    if read_mode(bytes)? != Mode::Canonical {
+        return Err(Error::Malformed(
+            "an adaptive file has no code lengths".into(),
+        ));
+    }
+
+    let mut pos = PREFIX_LEN;
+    let original_len = read_u64(bytes, &mut pos, "original length")?;
+    let block_size = read_u64(bytes, &mut pos, "block size")?;
+
+    if block_size == 0 && original_len != 0 {
+        return Err(Error::Malformed("the block size is 0".into()));
+    }
+
+    let mut blocks: Vec<Block> = Vec::new();
+    let mut remaining = original_len;
+
+    while remaining > 0 {
+        let block = blocks.len();
+        let truncated = || Error::Truncated(format!("the file ended in block {block}'s table"));
+
+        let (lengths, table) = match bytes.get(pos) {
+            Some(&STORED_TABLE) => {
+                let (lengths, lengths_len) =
+                    canonical::read_lengths(&bytes[pos + 1..]).map_err(|e| e.in_block(block))?;
+                pos += 1 + lengths_len;
+                (lengths, TableSource::Stored)
+            }
+            Some(&PREVIOUS_TABLE) => {
+                let Some(previous) = blocks.last() else {
+                    return Err(Error::Malformed(
+                        "the first block reuses a table, but there's no previous one".into(),
+                    ));
+                };
+                pos += 1;
+                (previous.lengths, TableSource::Previous)
+            }
+            Some(&PRESET_TABLE) => {
+                let &id = bytes.get(pos + 1).ok_or_else(truncated)?;
+                let preset = Preset::from_id(id).ok_or_else(|| {
+                    Error::Malformed(format!("block {block} uses an unknown built-in table {id}"))
+                })?;
+                pos += 2;
+                (*preset.lengths(), TableSource::Preset(preset))
+            }
+            Some(&CUSTOM_TABLE) => {
+                let checksum = bytes
+                    .get(pos + 1..)
+                    .and_then(|rest| rest.first_chunk::<CHECKSUM_LEN>())
+                    .ok_or_else(truncated)?;
+                let expected = u32::from_le_bytes(*checksum);
+                pos += 1 + CHECKSUM_LEN;
+
+                let given = custom_table.map(presets::checksum);
+                match custom_table {
+                    Some(lengths) if given == Some(expected) => {
+                        (*lengths, TableSource::Custom(expected))
+                    }
+                    _ => return Err(Error::CustomTable { expected, given }),
+                }
+            }
+            Some(table) => {
+                return Err(Error::Malformed(format!(
+                    "block {block} has an unknown table byte {table}"
+                )))
+            }
+            None => {
+                return Err(Error::Truncated(format!(
+                    "the file ended before block {block}"
+                )))
+            }
+        };
+
+        let data_len = read_u64(bytes, &mut pos, "data length")?;
+        let Some(data) = usize::try_from(data_len)
+            .ok()
+            .and_then(|data_len| Some(pos..pos.checked_add(data_len)?))
+            .filter(|data| data.end <= bytes.len())
+        else {
+            return Err(Error::Truncated(format!(
+                "block {block} has {data_len} bytes of data, past the end of the file"
+            )));
+        };
+        pos = data.end;
+
+        // every code is at least a bit
+        let block_len = remaining.min(block_size);
+        if block_len > data_len.saturating_mul(8) {
+            return Err(Error::Malformed(format!(
+                "block {block} is {block_len} bytes, more than {data_len} bytes of data can hold"
+            )));
+        }
+        remaining -= block_len;
+
+        blocks.push(Block {
+            len: block_len,
+            lengths,
+            table,
+            data,
+        });
+    }
+
+    let Some(checksum) = bytes
+        .get(pos..)
+        .and_then(|rest| rest.first_chunk::<CHECKSUM_LEN>())
+    else {
+        return Err(Error::Truncated(
+            "the file ended before the checksum".into(),
+        ));
+    };
+    pos += CHECKSUM_LEN;
+
+    if pos != bytes.len() {
+        return Err(Error::Malformed(format!(
+            "{} bytes after the checksum",
+            bytes.len() - pos
+        )));
+    }
+
+    let data_len: usize = blocks.iter().map(|block| block.data.len()).sum();
+
+    Ok(Header {
+        original_len,
+        block_size,
+        blocks,
+        checksum: u32::from_le_bytes(*checksum),
+        len: pos - data_len,
+    })
+}
+
+// the tree's code lengths, or package-merge's if the tree is too deep
+pub fn code_lengths(freq_map: &HashMap<u8, u64>, max_code_len: u8) -> anyhow::Result<Lengths> {
+    // an empty input has no codes at all
+    let Some(root) = build_huffman_tree(freq_map.clone()) else {
+        return Ok([0; 256]);
+    };
+
+    let lengths = canonical::code_lengths(&root);
+    let max_code_len = max_code_len.min(canonical::MAX_CODE_LEN);
+
+    if lengths.iter().any(|&len| len > max_code_len) {
+        return canonical::limited_code_lengths(freq_map, max_code_len);
+    }
+
+    Ok(lengths)
+}
+
+pub fn compress(input: &[u8]) -> anyhow::Result<Vec<u8>> {
+    compress_with(input, &Config::DEFAULT)
+}
+
+// the bits a block's data takes with `lengths`, or `None` if a symbol has no code in them
+fn encoded_bits(freq_map: &HashMap<u8, u64>, lengths: &Lengths) -> Option<u64> {
+    freq_map
+        .iter()
+        .map(|(&symbol, &frequency)| match lengths[symbol as usize] {
+            0 => None,
+            len => Some(frequency * len as u64),
+        })
+        .sum()
+}
+
+pub fn compress_with(input: &[u8], config: &Config) -> anyhow::Result<Vec<u8>> {
+    if config.block_size == 0 {
+        bail!("the block size can't be 0");
+    }
+    if !(1..=canonical::MAX_CODE_LEN).contains(&config.max_code_len) {
+        bail!(
+            "the longest code has to be 1 to {} bits, not {}",
+            canonical::MAX_CODE_LEN,
+            config.max_code_len
+        );
+    }
+
+    let mut output = Vec::with_capacity(PREFIX_LEN + 2 * LENGTH_LEN + input.len() / 2);
+    output.extend_from_slice(&Mode::Canonical.prefix());
+    output.extend_from_slice(&(input.len() as u64).to_le_bytes());
+    output.extend_from_slice(&config.block_size.to_le_bytes());
+
+    let block_size = usize::try_from(config.block_size).unwrap_or(usize::MAX);
+    let custom = config
+        .custom_table
+        .map(|lengths| (TableSource::Custom(presets::checksum(&lengths)), lengths));
+    let mut previous: Option<Lengths> = None;
+
+    for block in input.chunks(block_size) {
+        let freq_map = build_frequency_map(block);
+        let lengths = code_lengths(&freq_map, config.max_code_len)?;
+
+        let mut stored = Vec::new();
+        canonical::write_lengths(&lengths, &mut stored);
+
+        // every table the block could use, with the bytes it takes to say which one. the
+        // first of the cheapest wins, so a tie goes to the tables that aren't stored
+        let previous_table = previous.map(|lengths| (TableSource::Previous, lengths, 0));
+        let preset_tables = Preset::ALL
+            .into_iter()
+            .filter(|_| config.presets)
+            .map(|preset| (TableSource::Preset(preset), *preset.lengths(), 1));
+        let custom_table = custom.map(|(source, lengths)| (source, lengths, CHECKSUM_LEN));
+        let stored_table = (TableSource::Stored, lengths, stored.len());
+
+        let (source, lengths, _) = previous_table
+            .into_iter()
+            .chain(preset_tables)
+            .chain(custom_table)
+            .chain([stored_table])
+            .filter(|(_, lengths, _)| lengths.iter().all(|&len| len <= config.max_code_len))
+            // a table without a code for one of the block's symbols can't be used at all
+            .filter_map(|(source, lengths, len)| {
+                let bits = encoded_bits(&freq_map, &lengths)?;
+                Some((source, lengths, bits.saturating_add(len as u64 * 8)))
+            })
+            .min_by_key(|&(_, _, bits)| bits)
+            .ok_or_else(|| {
+                anyhow!(
+                    "no table has codes of at most {} bits for every symbol",
+                    config.max_code_len
+                )
+            })?;
+
+        match source {
+            TableSource::Stored => {
+                output.push(STORED_TABLE);
+                output.extend_from_slice(&stored);
+            }
+            TableSource::Previous => output.push(PREVIOUS_TABLE),
+            TableSource::Preset(preset) => output.extend_from_slice(&[PRESET_TABLE, preset.id()]),
+            TableSource::Custom(checksum) => {
+                output.push(CUSTOM_TABLE);
+                output.extend_from_slice(&checksum.to_le_bytes());
+            }
+        }
+
+        let encoded = encode(block, &canonical::codes(&lengths));
+        output.extend_from_slice(&(encoded.len() as u64).to_le_bytes());
+        output.extend_from_slice(&encoded);
+
+        previous = Some(lengths);
+    }
+
+    output.extend_from_slice(&crc32::checksum(input).to_le_bytes());
+
+    Ok(output)
+}
+
+pub fn decompress(bytes: &[u8]) -> Result<Vec<u8>, Error> {
+    decompress_with(bytes, None)
+}
+
+// `custom_table` is only needed if the file was compressed with one
+pub fn decompress_with(bytes: &[u8], custom_table: Option<&Lengths>) -> Result<Vec<u8>, Error> {
+    if read_mode(bytes)? == Mode::Adaptive {
+        return adaptive::decode(&bytes[PREFIX_LEN..]);
+    }
+
+    let header = read_header_with(bytes, custom_table)?;
+    let mut output = Vec::with_capacity(header.original_len as usize);
+    let mut table = None;
+
+    for (i, block) in header.blocks.iter().enumerate() {
+        // `read_header` makes sure the first block has its own table
+        let table = match (&mut table, block.table) {
+            (Some(table), TableSource::Previous) => table,
+            (slot, _) => slot.insert(DecodeTable::new(&block.lengths)?),
+        };
+
+        let decoded = table
+            .decode(&bytes[block.data.clone()], block.len as usize)
+            .map_err(|e| e.in_block(i))?;
+        output.extend_from_slice(&decoded);
+    }
+
+    let actual = crc32::checksum(&output);
+    if actual != header.checksum {
+        return Err(Error::Checksum {
+            expected: header.checksum,
+            actual,
+        });
+    }
+
+    Ok(output)
+}
+
+// adaptive compression as the input comes in, it never holds more than a buffer of it
+pub fn compress_adaptive(mut input: impl Read, mut output: impl Write) -> anyhow::Result<()> {
+    output.write_all(&Mode::Adaptive.prefix())?;
+
+    let mut encoder = adaptive::Encoder::new(output);
+    std::io::copy(&mut input, &mut encoder)?;
+    encoder.finish()?.flush()?;
+
+    Ok(())
+}
+
+pub fn decompress_stream(input: impl Read, output: impl Write) -> Result<(), Error> {
+    decompress_stream_with(input, output, None)
+}
+
+// adaptive files are decoded as they're read, canonical ones are read whole first
+pub fn decompress_stream_with(
+    mut input: impl Read,
+    mut output: impl Write,
+    custom_table: Option<&Lengths>,
+) -> Result<(), Error> {
+    let mut prefix = Vec::with_capacity(PREFIX_LEN);
+    input
+        .by_ref()
+        .take(PREFIX_LEN as u64)
+        .read_to_end(&mut prefix)?;
+
+    match read_mode(&prefix)? {
+        Mode::Adaptive => {
+            std::io::copy(&mut adaptive::Decoder::new(input), &mut output)?;
+        }
+        Mode::Canonical => {
+            let mut bytes = prefix;
+            input.read_to_end(&mut bytes)?;
+            output.write_all(&decompress_with(&bytes, custom_table)?)?;
+        }
+    }
+
+    output.flush()?;
+    Ok(())
+}
@@ -1,293 +1,428 @@
-use std::cmp::Ordering;
-use std::collections::{BinaryHeap, HashMap};
-use std::fs::File;
-use std::io::{Read, Write};
-
-#[derive(Debug)]
-enum Node {
-    Leaf {
-        symbol: u8,
-        frequency: u32,
-    }, // Use u8 for ASCII
-    Internal {
-        frequency: u32,
-        left: Box<Node>,
-        right: Box<Node>,
-    },
+use anyhow::{bail, Context};
+use clap::{Parser, Subcommand};
+use huffman::{
+    canonical::{self, Lengths},
+    inspect, presets,
+    tree::build_frequency_map,
+};
+use std::{
+    fs::File,
+    io::{self, BufWriter, Read, Write},
+    path::PathBuf,
+};
+
+#[derive(Parser)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
 }
 
-impl Node {
-    fn frequency(&self) -> u32 {
-        match self {
-            Node::Leaf { frequency, .. } | Node::Internal { frequency, .. } => *frequency,
-        }
-    }
+#[derive(Subcommand)]
+enum Command {
+    /// compress a file into the .huf format
+    Compress(CompressArgs),
+    /// decompress a .huf file
+    Decompress(CodecArgs),
+    /// print the code of every symbol, of a file or the one a .huf file stores
+    Inspect(InspectArgs),
+    /// make a custom code table from a sample of the data it's for
+    Table(TableArgs),
+}
+
+#[derive(clap::Args)]
+struct CompressArgs {
+    #[command(flatten)]
+    codec: CodecArgs,
+    /// the longest code to use, in bits
+    #[arg(short, long, default_value_t = huffman::Config::DEFAULT.max_code_len,
+        value_parser = clap::value_parser!(u8).range(1..=huffman::canonical::MAX_CODE_LEN as i64))]
+    max_code_len: u8,
+    /// the bytes of input that get their own code table
+    #[arg(short, long, default_value_t = huffman::Config::DEFAULT.block_size,
+        value_parser = clap::value_parser!(u64).range(1..))]
+    block_size: u64,
+    /// don't use the built-in tables, every block stores its own or reuses the previous one's
+    #[arg(long)]
+    no_presets: bool,
+    /// one pass adaptive huffman, it streams instead of reading the whole input first
+    #[arg(short, long,
+        conflicts_with_all = ["max_code_len", "block_size", "no_presets", "table"])]
+    adaptive: bool,
+}
+
+#[derive(clap::Args)]
+struct CodecArgs {
+    /// the file to read, defaults to stdin
+    input: Option<String>,
+    /// where to write the output, defaults to stdout
+    #[arg(short, long)]
+    output: Option<String>,
+    /// print the sizes to stderr
+    #[arg(short, long)]
+    stats: bool,
+    /// a custom table from `huffman table`, the same one is needed to decompress
+    #[arg(short, long)]
+    table: Option<String>,
+}
+
+#[derive(clap::Args)]
+struct InspectArgs {
+    /// the file to read, defaults to stdin
+    input: Option<String>,
+    /// which block of a .huf file to inspect
+    #[arg(short, long, default_value_t = 0)]
+    block: usize,
+    /// write the tree as a Graphviz DOT file
+    #[arg(short, long)]
+    dot: Option<String>,
+    /// the custom table a .huf file was compressed with
+    #[arg(short, long)]
+    table: Option<String>,
+}
+
+#[derive(clap::Args)]
+struct TableArgs {
+    /// the sample to read, defaults to stdin
+    sample: Option<String>,
+    /// where to write the table, defaults to stdout
+    #[arg(short, long)]
+    output: Option<String>,
+    /// the longest code to use, in bits
+    #[arg(short, long, default_value_t = huffman::Config::DEFAULT.max_code_len,
+        value_parser = clap::value_parser!(u8).range(8..=huffman::canonical::MAX_CODE_LEN as i64))]
+    max_code_len: u8,
 }
 
-#[derive(Debug)]
-struct MinNode(Box<Node>);
+fn read_input(input: Option<&str>) -> anyhow::Result<Vec<u8>> {
+    let mut buffer = Vec::new();
 
-impl PartialEq for MinNode {
-    fn eq(&self, other: &Self) -> bool {
-        self.0.frequency() == other.0.frequency()
+    match input {
+        Some(file) if file != "-" => {
+            File::open(file)
+                .with_context(|| format!("Failed to open file: {file}"))?
+                .read_to_end(&mut buffer)?;
+        }
+        _ => {
+            io::stdin()
+                .read_to_end(&mut buffer)
+                .context("Failed to read from the stdin")?;
+        }
     }
+
+    Ok(buffer)
 }
-impl Eq for MinNode {}
-impl Ord for MinNode {
-    fn cmp(&self, other: &Self) -> Ordering {
-        other.0.frequency().cmp(&self.0.frequency())
+
+fn open_input(input: Option<&str>) -> anyhow::Result<Box<dyn Read>> {
+    match input {
+        Some(file) if file != "-" => Ok(Box::new(
+            File::open(file).with_context(|| format!("Failed to open file: {file}"))?,
+        )),
+        _ => Ok(Box::new(io::stdin().lock())),
     }
 }
-impl PartialOrd for MinNode {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+
+fn open_output(output: Option<&str>) -> anyhow::Result<Box<dyn Write>> {
+    match output {
+        Some(file) if file != "-" => Ok(Box::new(BufWriter::new(
+            File::create(file).with_context(|| format!("Failed to create file: {file}"))?,
+        ))),
+        _ => Ok(Box::new(BufWriter::new(io::stdout().lock()))),
     }
 }
 
-struct BitWriter {
-    bits: Vec<u8>,
-    current_byte: u8,
-    bit_count: u8,
+// a decompressed file goes to a temp file next to it first, and only replaces `target`
+// once everything decoded, so a bad input never clobbers what's there. the temp file is
+// only created on the first write, and a canonical file is decoded whole before that
+struct PendingFile {
+    target: PathBuf,
+    temp: PathBuf,
+    file: Option<BufWriter<File>>,
 }
 
-impl BitWriter {
-    fn new() -> Self {
-        BitWriter {
-            bits: Vec::new(),
-            current_byte: 0,
-            bit_count: 0,
+impl PendingFile {
+    fn new(target: &str) -> Self {
+        let target = PathBuf::from(target);
+        let name = target
+            .file_name()
+            .map_or("output".into(), |name| name.to_string_lossy());
+        let temp = target.with_file_name(format!(".{name}.{}.tmp", std::process::id()));
+
+        PendingFile {
+            target,
+            temp,
+            file: None,
         }
     }
-    fn write_bit(&mut self, bit: bool) {
-        if bit {
-            self.current_byte |= 1 << (7 - self.bit_count);
-        }
-        self.bit_count += 1;
-        if self.bit_count == 8 {
-            self.bits.push(self.current_byte);
-            self.current_byte = 0;
-            self.bit_count = 0;
+
+    fn finish(mut self) -> anyhow::Result<()> {
+        let context = || format!("Failed to write file: {}", self.target.display());
+
+        // nothing was written, an empty file
+        let Some(mut file) = self.file.take() else {
+            File::create(&self.target).with_context(context)?;
+            return Ok(());
+        };
+
+        let flushed = file.flush();
+        drop(file);
+
+        let result = flushed.and_then(|()| std::fs::rename(&self.temp, &self.target));
+        if result.is_err() {
+            let _ = std::fs::remove_file(&self.temp);
         }
+
+        result.with_context(context)
     }
-    fn write_bits(&mut self, bits: &[bool]) {
-        for &bit in bits {
-            self.write_bit(bit);
-        }
+}
+
+impl Write for PendingFile {
+    fn write(&mut self, buffer: &[u8]) -> io::Result<usize> {
+        let file = match &mut self.file {
+            Some(file) => file,
+            None => self.file.insert(BufWriter::new(File::create(&self.temp)?)),
+        };
+
+        file.write(buffer)
     }
-    fn flush(&mut self) {
-        if self.bit_count > 0 {
-            self.bits.push(self.current_byte);
-            self.bit_count = 0;
-        }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.as_mut().map_or(Ok(()), |file| file.flush())
     }
-    fn into_bytes(self) -> Vec<u8> {
-        self.bits
+}
+
+// it wasn't finished, so the output is incomplete
+impl Drop for PendingFile {
+    fn drop(&mut self) {
+        if let Some(file) = self.file.take() {
+            drop(file);
+            let _ = std::fs::remove_file(&self.temp);
+        }
     }
 }
 
-struct BitReader<'a> {
-    bytes: &'a [u8],
-    pos: usize,
-    bit_pos: u8,
+// counts the bytes going through, for the stats of the streams
+struct Counted<T> {
+    inner: T,
+    count: u64,
 }
 
-impl<'a> BitReader<'a> {
-    fn new(bytes: &'a [u8]) -> Self {
-        BitReader {
-            bytes,
-            pos: 0,
-            bit_pos: 0,
-        }
-    }
-    fn read_bit(&mut self) -> Option<bool> {
-        if self.pos >= self.bytes.len() {
-            return None;
-        }
-        let bit = (self.bytes[self.pos] & (1 << (7 - self.bit_pos))) != 0;
-        self.bit_pos += 1;
-        if self.bit_pos == 8 {
-            self.pos += 1;
-            self.bit_pos = 0;
-        }
-        Some(bit)
+impl<T> Counted<T> {
+    fn new(inner: T) -> Self {
+        Counted { inner, count: 0 }
     }
 }
 
-fn build_frequency_map(input: &[u8]) -> HashMap<u8, u32> {
-    let mut freq_map = HashMap::new();
-    for &b in input {
-        *freq_map.entry(b).or_insert(0) += 1;
+impl<T: Read> Read for Counted<T> {
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        let len = self.inner.read(buffer)?;
+        self.count += len as u64;
+        Ok(len)
     }
-    freq_map
 }
 
-fn build_huffman_tree(freq_map: HashMap<u8, u32>) -> Option<Node> {
-    let mut heap: BinaryHeap<MinNode> = freq_map
-        .into_iter()
-        .map(|(symbol, frequency)| MinNode(Box::new(Node::Leaf { symbol, frequency })))
-        .collect();
-    if heap.is_empty() {
-        return None;
+impl<T: Write> Write for Counted<T> {
+    fn write(&mut self, buffer: &[u8]) -> io::Result<usize> {
+        let len = self.inner.write(buffer)?;
+        self.count += len as u64;
+        Ok(len)
     }
-    while heap.len() > 1 {
-        let left = heap.pop().unwrap().0;
-        let right = heap.pop().unwrap().0;
-        heap.push(MinNode(Box::new(Node::Internal {
-            frequency: left.frequency() + right.frequency(),
-            left,
-            right,
-        })));
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
     }
-    Some(*heap.pop().unwrap().0)
 }
 
-fn generate_codes(root: &Node) -> HashMap<u8, Vec<bool>> {
-    let mut codes = HashMap::new();
-    fn traverse(node: &Node, code: Vec<bool>, codes: &mut HashMap<u8, Vec<bool>>) {
-        match node {
-            Node::Leaf { symbol, .. } => {
-                codes.insert(*symbol, code);
-            }
-            Node::Internal { left, right, .. } => {
-                let mut left_code = code.clone();
-                left_code.push(false);
-                traverse(left, left_code, codes);
-                let mut right_code = code;
-                right_code.push(true);
-                traverse(right, right_code, codes);
-            }
+fn read_table(file: Option<&str>) -> anyhow::Result<Option<Lengths>> {
+    let Some(file) = file else {
+        return Ok(None);
+    };
+
+    let bytes = std::fs::read(file).with_context(|| format!("Failed to read file: {file}"))?;
+    let table = presets::read_table(&bytes).with_context(|| format!("Invalid table: {file}"))?;
+
+    Ok(Some(table))
+}
+
+fn write_output(output: Option<&str>, contents: &[u8]) -> anyhow::Result<()> {
+    match output {
+        Some(file) if file != "-" => {
+            std::fs::write(file, contents).with_context(|| format!("Failed to write file: {file}"))
         }
+        _ => Ok(io::stdout().lock().write_all(contents)?),
     }
-    traverse(root, Vec::new(), &mut codes);
-    codes
 }
 
-fn encode(input: &[u8], codes: &HashMap<u8, Vec<bool>>) -> Vec<u8> {
-    let mut writer = BitWriter::new();
-    for &b in input {
-        writer.write_bits(&codes[&b]);
+// the header is only known for canonical files, the streams don't keep the bytes around
+fn print_stats(original: u64, compressed: u64, header: Option<usize>) {
+    eprintln!("original:   {original} bytes");
+
+    match header {
+        Some(header) => eprintln!("compressed: {compressed} bytes ({header} of them the header)"),
+        None => eprintln!("compressed: {compressed} bytes"),
+    }
+
+    if original != 0 {
+        eprintln!(
+            "ratio:      {:.2}%",
+            compressed as f64 / original as f64 * 100.0
+        );
     }
-    writer.flush();
-    writer.into_bytes()
 }
 
-fn decode(encoded: &[u8], root: &Node, original_len: usize) -> Vec<u8> {
-    let mut result = Vec::new();
-    let mut reader = BitReader::new(encoded);
-    let mut current = root;
-    while let Some(bit) = reader.read_bit() {
-        current = match current {
-            Node::Leaf { symbol, .. } => {
-                result.push(*symbol);
-                if result.len() >= original_len {
-                    break;
-                }
-                root
-            }
-            Node::Internal { left, right, .. } => {
-                if bit {
-                    right.as_ref()
-                } else {
-                    left.as_ref()
-                }
-            }
-        };
-        if let Node::Leaf { symbol, .. } = current {
-            result.push(*symbol);
-            if result.len() >= original_len {
-                break;
+// a .huf file's codes come from its stored lengths, and the frequencies from
+// decompressing it, anything else gets the canonical codes `compress` would store for it
+fn inspect(args: InspectArgs) -> anyhow::Result<()> {
+    let input = read_input(args.input.as_deref())?;
+    let table = read_table(args.table.as_deref())?;
+
+    let (root, freq_map) = match huffman::read_mode(&input) {
+        Ok(huffman::Mode::Canonical) => {
+            let header = huffman::read_header_with(&input, table.as_ref())?;
+            let decompressed = huffman::decompress_with(&input, table.as_ref())?;
+
+            let Some(block) = header.blocks.get(args.block) else {
+                bail!(
+                    "there's no block {}, the file has {}",
+                    args.block,
+                    header.blocks.len()
+                );
+            };
+
+            let start = (args.block as u64 * header.block_size) as usize;
+            let freq_map = build_frequency_map(&decompressed[start..start + block.len as usize]);
+            let root = canonical::build_tree(&block.lengths)?;
+
+            (inspect::weigh(&root, &freq_map), freq_map)
+        }
+        Ok(huffman::Mode::Adaptive) => {
+            bail!("an adaptive file doesn't store a tree, it changes with every symbol")
+        }
+        Err(huffman::Error::NotHuf | huffman::Error::Truncated(_)) => {
+            let freq_map = build_frequency_map(&input);
+            if freq_map.is_empty() {
+                bail!("an empty input has no codes to inspect");
             }
-            current = root;
+
+            let lengths = huffman::code_lengths(&freq_map, huffman::Config::DEFAULT.max_code_len)?;
+            let root = canonical::build_tree(&lengths)?;
+
+            (inspect::weigh(&root, &freq_map), freq_map)
         }
+        Err(e) => return Err(e.into()),
+    };
+
+    let report = inspect::report(&root, &freq_map);
+
+    println!("symbol  frequency  length  code");
+    for symbol in &report.symbols {
+        println!(
+            "{:<6}  {:>9}  {:>6}  {}",
+            inspect::symbol_name(symbol.symbol),
+            symbol.frequency,
+            symbol.code.len(),
+            inspect::code_string(&symbol.code)
+        );
+    }
+
+    println!();
+    println!(
+        "symbols:             {} different, {} in total",
+        report.symbols.len(),
+        report.total
+    );
+    println!("average code length: {:.4} bits", report.average_len);
+    println!("entropy:             {:.4} bits", report.entropy);
+
+    if let Some(dot) = args.dot {
+        std::fs::write(&dot, inspect::to_dot(&root))
+            .with_context(|| format!("Failed to write file: {dot}"))?;
     }
-    result
+
+    Ok(())
 }
 
-fn serialize_tree(root: &Node) -> Vec<u8> {
-    let mut writer = BitWriter::new();
-    fn serialize(node: &Node, writer: &mut BitWriter) {
-        match node {
-            Node::Leaf { symbol, .. } => {
-                writer.write_bit(true); // 1 for leaf
-                for i in (0..8).rev() {
-                    writer.write_bit((symbol & (1 << i)) != 0);
-                }
+fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+
+    match args.command {
+        Command::Compress(CompressArgs {
+            codec: args,
+            adaptive: true,
+            ..
+        }) => {
+            let mut input = Counted::new(open_input(args.input.as_deref())?);
+            let mut output = Counted::new(open_output(args.output.as_deref())?);
+
+            huffman::compress_adaptive(&mut input, &mut output)?;
+
+            if args.stats {
+                print_stats(input.count, output.count, None);
             }
-            Node::Internal { left, right, .. } => {
-                writer.write_bit(false); // 0 for internal
-                serialize(left, writer);
-                serialize(right, writer);
+        }
+        Command::Compress(CompressArgs {
+            codec: args,
+            max_code_len,
+            block_size,
+            no_presets,
+            ..
+        }) => {
+            let mut config = huffman::Config::DEFAULT
+                .with_max_code_len(max_code_len)
+                .with_block_size(block_size)
+                .with_presets(!no_presets);
+            if let Some(table) = read_table(args.table.as_deref())? {
+                config = config.with_custom_table(table);
+            }
+
+            let input = read_input(args.input.as_deref())?;
+            let compressed = huffman::compress_with(&input, &config)?;
+
+            write_output(args.output.as_deref(), &compressed)?;
+
+            if args.stats {
+                let header = huffman::read_header_with(&compressed, config.custom_table.as_ref())?;
+                print_stats(
+                    input.len() as u64,
+                    compressed.len() as u64,
+                    Some(header.len),
+                );
             }
         }
-    }
-    serialize(root, &mut writer);
-    writer.flush();
-    writer.into_bytes()
-}
+        Command::Decompress(args) => {
+            let table = read_table(args.table.as_deref())?;
+            let mut input = Counted::new(open_input(args.input.as_deref())?);
+
+            let mut pending = args
+                .output
+                .as_deref()
+                .filter(|&file| file != "-")
+                .map(PendingFile::new);
+            let mut output = Counted::new(match &mut pending {
+                Some(file) => Box::new(file) as Box<dyn Write>,
+                None => Box::new(BufWriter::new(io::stdout().lock())),
+            });
+
+            huffman::decompress_stream_with(&mut input, &mut output, table.as_ref())?;
+            let decompressed = output.count;
+            drop(output);
 
-fn deserialize_tree(bytes: &[u8]) -> (Node, usize) {
-    let mut reader = BitReader::new(bytes);
-    fn deserialize(reader: &mut BitReader) -> Node {
-        if reader.read_bit().unwrap() {
-            let mut symbol = 0;
-            for i in (0..8).rev() {
-                if reader.read_bit().unwrap() {
-                    symbol |= 1 << i;
-                }
+            if let Some(file) = pending {
+                file.finish()?;
             }
-            Node::Leaf {
-                symbol,
-                frequency: 0,
-            } // Frequency not needed for decoding
-        } else {
-            let left = Box::new(deserialize(reader));
-            let right = Box::new(deserialize(reader));
-            Node::Internal {
-                frequency: 0,
-                left,
-                right,
+
+            if args.stats {
+                print_stats(decompressed, input.count, None);
             }
         }
-    }
-    let tree = deserialize(&mut reader);
-    (tree, reader.pos + (if reader.bit_pos > 0 { 1 } else { 0 }))
-}
-
-fn main() {
-    println!("{:#?}", 0xC0A80101_u32.to_be_bytes());
-    let mut input = Vec::new();
-    std::io::stdin().read_to_end(&mut input).unwrap(); // ~800 bytes
-    let input = input.as_slice();
-    println!("Original size: {} bytes", input.len());
-
-    let freq_map = build_frequency_map(input);
-    let root = build_huffman_tree(freq_map).unwrap();
-    let codes = generate_codes(&root);
-    let encoded = encode(input, &codes);
-    let tree_data = serialize_tree(&root);
-    let total_size = encoded.len() + tree_data.len() + 8; // 8 bytes for header (lengths)
-
-    println!("Encoded size: {} bytes", encoded.len());
-    println!("Tree size: {} bytes", tree_data.len());
-    println!("Total size: {} bytes", total_size);
-
-    let mut file = File::create("compressed.huf").unwrap();
-    file.write_all(&(input.len() as u64).to_le_bytes()).unwrap(); // Original length
-    file.write_all(&(tree_data.len() as u64).to_le_bytes())
-        .unwrap(); // Tree length
-    file.write_all(&tree_data).unwrap();
-    file.write_all(&encoded).unwrap();
-
-    let mut file = File::open("compressed.huf").unwrap();
-    let mut buffer = Vec::new();
-    file.read_to_end(&mut buffer).unwrap();
+        Command::Inspect(args) => inspect(args)?,
+        Command::Table(args) => {
+            let sample = read_input(args.sample.as_deref())?;
+            let table = presets::table_from_sample(&sample, args.max_code_len)?;
 
-    let original_len = u64::from_le_bytes(buffer[0..8].try_into().unwrap()) as usize;
-    let tree_len = u64::from_le_bytes(buffer[8..16].try_into().unwrap()) as usize;
-    let (tree, tree_bytes_read) = deserialize_tree(&buffer[16..16 + tree_len]);
-    let decoded = decode(&buffer[16 + tree_bytes_read..], &tree, original_len);
+            write_output(args.output.as_deref(), &presets::write_table(&table))?;
+        }
+    }
 
-    println!("Decoded size: {} bytes", decoded.len());
-    assert_eq!(input, decoded.as_slice());
-    println!("Success: Decompression matches original!");
+    Ok(())
 }
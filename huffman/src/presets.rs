@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use crate::canonical::{self, Lengths};
+use crate::crc32;
+use crate::error::Error;
+
+/*
+* code tables that don't have to be stored, for inputs too small to pay for their own.
+* a block that uses one only records which, and the encoder picks one whenever its codes
+* cost less than the block's own codes plus their table
+*
+* the built-in ones come from a rough count of every symbol in that kind of data, and
+* every other byte counts once, so they have a code for everything and can encode any
+* input, just badly if it's nothing like what they were made for
+*
+* a custom table is made the same way from a sample, and saved as its code lengths in
+* the same format a block stores them (see `canonical::write_lengths`). the file only
+* keeps its checksum, so it has to be given again to decompress
+*/
+
+// what `Config::DEFAULT` allows, so the presets fit unless the limit is lowered
+const MAX_CODE_LEN: u8 = 15;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Preset {
+    English,
+    Json,
+    // every byte gets 8 bits, for data that doesn't compress
+    Flat,
+}
+
+// lowercase letters in english text, in thousandths
+const LETTERS: [u64; 26] = [
+    82, 15, 28, 43, 127, 22, 20, 61, 70, 2, 8, 40, 24, 67, 75, 19, 1, 60, 63, 91, 28, 10, 24, 2,
+    20, 1,
+];
+
+const ENGLISH: &[(u8, u64)] = &[
+    (b' ', 180),
+    (b'\n', 8),
+    (b'.', 6),
+    (b',', 7),
+    (b'\'', 2),
+    (b'"', 2),
+    (b'-', 2),
+    (b'?', 1),
+    (b'!', 1),
+    (b';', 1),
+    (b':', 1),
+];
+
+const JSON: &[(u8, u64)] = &[
+    (b'"', 120),
+    (b':', 30),
+    (b',', 30),
+    (b' ', 60),
+    (b'\n', 15),
+    (b'{', 8),
+    (b'}', 8),
+    (b'[', 3),
+    (b']', 3),
+    (b'.', 4),
+    (b'-', 2),
+    (b'_', 5),
+];
+
+impl Preset {
+    pub const ALL: [Preset; 3] = [Preset::English, Preset::Json, Preset::Flat];
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Preset::English => "english",
+            Preset::Json => "json",
+            Preset::Flat => "flat",
+        }
+    }
+
+    // what's stored after the table byte
+    pub(crate) const fn id(self) -> u8 {
+        self as u8
+    }
+
+    pub(crate) fn from_id(id: u8) -> Option<Preset> {
+        Preset::ALL.into_iter().find(|preset| preset.id() == id)
+    }
+
+    fn counts(self) -> HashMap<u8, u64> {
+        let mut counts: HashMap<u8, u64> = (0..=255).map(|symbol| (symbol, 1)).collect();
+
+        let (letters, digits, symbols) = match self {
+            Preset::English => (1, 1, ENGLISH),
+            Preset::Json => (1, 100, JSON),
+            Preset::Flat => return counts,
+        };
+
+        for (i, &count) in LETTERS.iter().enumerate() {
+            // capitals are about a twentieth as common
+            counts.insert(b'a' + i as u8, count * 10 * letters);
+            counts.insert(b'A' + i as u8, (count / 2 * letters).max(1));
+        }
+        for digit in b'0'..=b'9' {
+            counts.insert(digit, 2 * digits);
+        }
+        for &(symbol, count) in symbols {
+            counts.insert(symbol, count * 10);
+        }
+
+        counts
+    }
+
+    pub fn lengths(self) -> &'static Lengths {
+        static TABLES: [OnceLock<Lengths>; 3] = [const { OnceLock::new() }; 3];
+
+        TABLES[self as usize].get_or_init(|| {
+            crate::code_lengths(&self.counts(), MAX_CODE_LEN)
+                .expect("every byte has a count, so there's always a table")
+        })
+    }
+}
+
+// a custom table for data like `sample`, every byte gets a code even if it's not in it
+pub fn table_from_sample(sample: &[u8], max_code_len: u8) -> anyhow::Result<Lengths> {
+    let mut counts: HashMap<u8, u64> = (0..=255).map(|symbol| (symbol, 1)).collect();
+    for &b in sample {
+        *counts.get_mut(&b).unwrap() += 1;
+    }
+
+    crate::code_lengths(&counts, max_code_len)
+}
+
+pub fn write_table(lengths: &Lengths) -> Vec<u8> {
+    let mut output = Vec::new();
+    canonical::write_lengths(lengths, &mut output);
+    output
+}
+
+pub fn read_table(bytes: &[u8]) -> Result<Lengths, Error> {
+    let (lengths, len) = canonical::read_lengths(bytes)?;
+
+    if len != bytes.len() {
+        return Err(Error::Malformed(format!(
+            "{} bytes after the table",
+            bytes.len() - len
+        )));
+    }
+
+    canonical::validate(&lengths)?;
+    Ok(lengths)
+}
+
+// what a block that uses a custom table stores to say which one
+pub fn checksum(lengths: &Lengths) -> u32 {
+    crc32::checksum(&write_table(lengths))
+}
@@ -0,0 +1,143 @@
+use crate::canonical::{self, Lengths};
+use crate::error::Error;
+
+/*
+* a table driven decoder, instead of walking the tree a bit at a time, the next
+* `PRIMARY_BITS` bits index a table of which symbol they start with and how long its
+* code is. a shorter code fills every entry it's a prefix of:
+*
+*   "10" with 4 primary bits fills 1000, 1001, 1010 and 1011
+*
+* codes longer than that share the primary entry of their first `PRIMARY_BITS` bits,
+* which points to a secondary table indexed by the bits after them, just big enough
+* for the longest code under it
+*/
+
+const PRIMARY_BITS: u8 = 10;
+
+#[derive(Debug, Clone, Copy)]
+enum Entry {
+    Symbol { symbol: u8, len: u8 },
+    // a secondary table at `offset`, indexed by the next `bits` bits
+    Table { offset: u32, bits: u8 },
+    // only a lone symbol's code "0" leaves codes unused, "1" is never written
+    Invalid,
+}
+
+pub struct DecodeTable {
+    primary: Vec<Entry>,
+    secondary: Vec<Entry>,
+    primary_bits: u8,
+}
+
+// the top `bits` bits of the buffer
+#[inline]
+fn peek(buffer: u64, bits: u8) -> usize {
+    buffer.checked_shr(64 - bits as u32).unwrap_or(0) as usize
+}
+
+impl DecodeTable {
+    pub fn new(lengths: &Lengths) -> Result<Self, Error> {
+        canonical::validate(lengths)?;
+
+        let codes = canonical::assign(lengths);
+        let max_len = codes.iter().map(|&(_, _, len)| len).max().unwrap_or(0);
+        let primary_bits = max_len.min(PRIMARY_BITS);
+
+        let mut primary = vec![Entry::Invalid; 1 << primary_bits];
+        let mut secondary = Vec::new();
+
+        // how many bits past the primary ones the longest code under each entry has
+        let mut extra_bits = vec![0u8; 1 << primary_bits];
+        for &(_, code, len) in codes.iter().filter(|&&(_, _, len)| len > primary_bits) {
+            let prefix = (code >> (len - primary_bits)) as usize;
+            extra_bits[prefix] = extra_bits[prefix].max(len - primary_bits);
+        }
+
+        for (prefix, &bits) in extra_bits.iter().enumerate().filter(|(_, &bits)| bits > 0) {
+            primary[prefix] = Entry::Table {
+                offset: secondary.len() as u32,
+                bits,
+            };
+            secondary.resize(secondary.len() + (1 << bits), Entry::Invalid);
+        }
+
+        for &(symbol, code, len) in &codes {
+            let entry = Entry::Symbol { symbol, len };
+
+            if len <= primary_bits {
+                let start = (code << (primary_bits - len)) as usize;
+                primary[start..start + (1 << (primary_bits - len))].fill(entry);
+            } else {
+                let extra = len - primary_bits;
+                let Entry::Table { offset, bits } = primary[(code >> extra) as usize] else {
+                    unreachable!("every long code's prefix points to a secondary table");
+                };
+
+                let start =
+                    offset as usize + ((code & ((1 << extra) - 1)) << (bits - extra)) as usize;
+                secondary[start..start + (1 << (bits - extra))].fill(entry);
+            }
+        }
+
+        Ok(DecodeTable {
+            primary,
+            secondary,
+            primary_bits,
+        })
+    }
+
+    pub fn decode(&self, encoded: &[u8], original_len: usize) -> Result<Vec<u8>, Error> {
+        let mut output = Vec::with_capacity(original_len);
+
+        // the next bits of the input, most significant first, and how many are real
+        let mut buffer = 0u64;
+        let mut count = 0u32;
+        let mut pos = 0;
+
+        while output.len() < original_len {
+            if let Some(word) = encoded.get(pos..pos + 8) {
+                // a whole word at once, only the bytes that fit are counted as read
+                let word = u64::from_be_bytes(word.try_into().unwrap());
+                buffer |= word >> count;
+                pos += (63 - count as usize) / 8;
+                count |= 56;
+            } else {
+                while count <= 56 && pos < encoded.len() {
+                    buffer |= (encoded[pos] as u64) << (56 - count);
+                    count += 8;
+                    pos += 1;
+                }
+            }
+
+            let entry = match self.primary[peek(buffer, self.primary_bits)] {
+                Entry::Table { offset, bits } => {
+                    self.secondary[offset as usize + peek(buffer << self.primary_bits, bits)]
+                }
+                entry => entry,
+            };
+
+            let Entry::Symbol { symbol, len } = entry else {
+                return Err(Error::Corrupted(format!(
+                    "invalid code after {} of the {original_len} bytes",
+                    output.len()
+                )));
+            };
+
+            // past the end the buffer is zero padded, so the lookup may have used bits
+            // that don't exist
+            if len as u32 > count {
+                return Err(Error::Truncated(format!(
+                    "the data ended after {} of the {original_len} bytes",
+                    output.len()
+                )));
+            }
+
+            output.push(symbol);
+            buffer <<= len;
+            count -= len as u32;
+        }
+
+        Ok(output)
+    }
+}
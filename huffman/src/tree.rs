@@ -0,0 +1,164 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+
+use crate::bits::{BitReader, BitWriter};
+
+#[derive(Debug)]
+pub enum Node {
+    Leaf {
+        symbol: u8,
+        frequency: u64,
+    }, // Use u8 for ASCII
+    Internal {
+        frequency: u64,
+        left: Box<Node>,
+        right: Box<Node>,
+    },
+}
+
+impl Node {
+    pub fn frequency(&self) -> u64 {
+        match self {
+            Node::Leaf { frequency, .. } | Node::Internal { frequency, .. } => *frequency,
+        }
+    }
+}
+
+// the second field is the order the node was made in, equal frequencies are broken by
+// it so the same input always builds the same tree
+#[derive(Debug)]
+struct MinNode(Box<Node>, usize);
+
+impl PartialEq for MinNode {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+impl Eq for MinNode {}
+impl Ord for MinNode {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .0
+            .frequency()
+            .cmp(&self.0.frequency())
+            .then(other.1.cmp(&self.1))
+    }
+}
+impl PartialOrd for MinNode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+pub fn build_frequency_map(input: &[u8]) -> HashMap<u8, u64> {
+    let mut freq_map = HashMap::new();
+    for &b in input {
+        *freq_map.entry(b).or_insert(0) += 1;
+    }
+    freq_map
+}
+
+pub fn build_huffman_tree(freq_map: HashMap<u8, u64>) -> Option<Node> {
+    // the map's order is random, so the leaves are sorted by symbol first
+    let mut leaves: Vec<(u8, u64)> = freq_map.into_iter().collect();
+    leaves.sort_unstable();
+
+    let mut heap: BinaryHeap<MinNode> = leaves
+        .into_iter()
+        .enumerate()
+        .map(|(order, (symbol, frequency))| {
+            MinNode(Box::new(Node::Leaf { symbol, frequency }), order)
+        })
+        .collect();
+    if heap.is_empty() {
+        return None;
+    }
+    let mut order = heap.len();
+    while heap.len() > 1 {
+        let left = heap.pop().unwrap().0;
+        let right = heap.pop().unwrap().0;
+        heap.push(MinNode(
+            Box::new(Node::Internal {
+                frequency: left.frequency() + right.frequency(),
+                left,
+                right,
+            }),
+            order,
+        ));
+        order += 1;
+    }
+    Some(*heap.pop().unwrap().0)
+}
+
+pub fn generate_codes(root: &Node) -> HashMap<u8, Vec<bool>> {
+    let mut codes = HashMap::new();
+    fn traverse(node: &Node, code: Vec<bool>, codes: &mut HashMap<u8, Vec<bool>>) {
+        match node {
+            Node::Leaf { symbol, .. } => {
+                codes.insert(*symbol, code);
+            }
+            Node::Internal { left, right, .. } => {
+                let mut left_code = code.clone();
+                left_code.push(false);
+                traverse(left, left_code, codes);
+                let mut right_code = code;
+                right_code.push(true);
+                traverse(right, right_code, codes);
+            }
+        }
+    }
+    // a lone symbol still needs a bit per occurrence, or there'd be nothing to decode
+    let code = match root {
+        Node::Leaf { .. } => vec![false],
+        Node::Internal { .. } => Vec::new(),
+    };
+    traverse(root, code, &mut codes);
+    codes
+}
+
+pub fn encode(input: &[u8], codes: &HashMap<u8, Vec<bool>>) -> Vec<u8> {
+    let mut writer = BitWriter::new();
+    for &b in input {
+        writer.write_bits(&codes[&b]);
+    }
+    writer.flush();
+    writer.into_bytes()
+}
+
+pub fn decode(encoded: &[u8], root: &Node, original_len: usize) -> Vec<u8> {
+    let mut result = Vec::new();
+    let mut reader = BitReader::new(encoded);
+    if let Node::Leaf { symbol, .. } = root {
+        while result.len() < original_len && reader.read_bit().is_some() {
+            result.push(*symbol);
+        }
+        return result;
+    }
+    let mut current = root;
+    while let Some(bit) = reader.read_bit() {
+        current = match current {
+            Node::Leaf { symbol, .. } => {
+                result.push(*symbol);
+                if result.len() >= original_len {
+                    break;
+                }
+                root
+            }
+            Node::Internal { left, right, .. } => {
+                if bit {
+                    right.as_ref()
+                } else {
+                    left.as_ref()
+                }
+            }
+        };
+        if let Node::Leaf { symbol, .. } = current {
+            result.push(*symbol);
+            if result.len() >= original_len {
+                break;
+            }
+            current = root;
+        }
+    }
+    result
+}
@@ -0,0 +1,65 @@
+mod common;
+
+use common::skewed_bytes;
+use huffman::{adaptive, compress_adaptive, decompress, decompress_stream};
+use std::io::Write;
+
+const SEED: u64 = 0x2545_F491_4F6C_DD1D;
+
+fn round_trip(input: &[u8]) {
+    let mut compressed = Vec::new();
+    compress_adaptive(input, &mut compressed).unwrap();
+
+    assert_eq!(decompress(&compressed).unwrap(), input);
+
+    let mut decompressed = Vec::new();
+    decompress_stream(compressed.as_slice(), &mut decompressed).unwrap();
+    assert_eq!(decompressed, input);
+}
+
+#[test]
+fn round_trips() {
+    round_trip(b"");
+    round_trip(b"a");
+    round_trip(&[0; 1000]);
+    round_trip(b"abracadabra, abracadabra");
+    round_trip(&(0..=255).collect::<Vec<u8>>());
+    round_trip(&(0..=255).rev().cycle().take(10_000).collect::<Vec<u8>>());
+    round_trip(&skewed_bytes(100_000, SEED));
+}
+
+#[test]
+fn compresses_skewed_data() {
+    let input = skewed_bytes(100_000, SEED);
+    let encoded = adaptive::encode(&input);
+
+    // within a percent of the two pass codes, which also store a table
+    let two_pass = huffman::compress(&input).unwrap();
+    assert!(
+        encoded.len() * 100 < two_pass.len() * 101,
+        "{} bytes against {}",
+        encoded.len(),
+        two_pass.len()
+    );
+}
+
+#[test]
+fn chunks_encode_like_one_write() {
+    let input = skewed_bytes(10_000, SEED);
+
+    let mut encoder = adaptive::Encoder::new(Vec::new());
+    for chunk in input.chunks(7) {
+        encoder.write_all(chunk).unwrap();
+    }
+
+    assert_eq!(encoder.finish().unwrap(), adaptive::encode(&input));
+}
+
+#[test]
+fn truncated_data_is_rejected() {
+    let encoded = adaptive::encode(b"abracadabra");
+
+    for len in 0..encoded.len() {
+        assert!(adaptive::decode(&encoded[..len]).is_err(), "{len} bytes");
+    }
+}
@@ -0,0 +1,109 @@
+mod common;
+
+use common::Xorshift;
+use huffman::{code_lengths, compress_with, decompress, read_header, Config, TableSource};
+use std::collections::HashMap;
+
+// some text and then bytes that use the whole range, what one table handles badly
+fn text_then_binary() -> Vec<u8> {
+    let text = b"the quick brown fox jumps over the lazy dog. ".repeat(2000);
+    let binary = Xorshift::new(0x2545_F491_4F6C_DD1D)
+        .take(text.len())
+        .map(|x| x as u8);
+
+    text.iter().copied().chain(binary).collect()
+}
+
+#[test]
+fn blocks_round_trip() {
+    let input = text_then_binary();
+    let short = &input[input.len() / 2 - 500..input.len() / 2 + 500];
+
+    for block_size in [1, 7] {
+        let config = Config::DEFAULT.with_block_size(block_size);
+        let compressed = compress_with(short, &config).unwrap();
+
+        assert_eq!(
+            decompress(&compressed).unwrap(),
+            short,
+            "blocks of {block_size}"
+        );
+    }
+
+    for block_size in [4096, input.len() as u64 / 2, input.len() as u64, u64::MAX] {
+        let config = Config::DEFAULT.with_block_size(block_size);
+        let compressed = compress_with(&input, &config).unwrap();
+
+        let header = read_header(&compressed).unwrap();
+        assert_eq!(
+            header.blocks.len() as u64,
+            (input.len() as u64).div_ceil(block_size)
+        );
+
+        assert_eq!(
+            decompress(&compressed).unwrap(),
+            input,
+            "blocks of {block_size}"
+        );
+    }
+}
+
+#[test]
+fn blocks_follow_changes_in_the_data() {
+    let input = text_then_binary();
+
+    let one_table = compress_with(&input, &Config::DEFAULT.with_block_size(u64::MAX)).unwrap();
+    let blocks = compress_with(&input, &Config::DEFAULT.with_block_size(8192)).unwrap();
+
+    assert!(
+        blocks.len() < one_table.len(),
+        "{} against {}",
+        blocks.len(),
+        one_table.len()
+    );
+}
+
+#[test]
+fn similar_blocks_reuse_the_table() {
+    let input = b"the quick brown fox jumps over the lazy dog. ".repeat(1000);
+    let compressed = compress_with(&input, &Config::DEFAULT.with_block_size(4500)).unwrap();
+
+    let header = read_header(&compressed).unwrap();
+    assert_ne!(header.blocks[0].table, TableSource::Previous);
+    assert!(header.blocks[1..]
+        .iter()
+        .all(|block| block.table == TableSource::Previous));
+
+    assert_eq!(decompress(&compressed).unwrap(), input);
+}
+
+#[test]
+fn new_symbols_need_a_new_table() {
+    let mut input = b"aaaabbbc".repeat(100);
+    input.extend(b"xyz".repeat(100));
+
+    let config = Config::DEFAULT.with_block_size(800).with_presets(false);
+    let compressed = compress_with(&input, &config).unwrap();
+
+    let header = read_header(&compressed).unwrap();
+    assert_eq!(header.blocks[1].table, TableSource::Stored);
+
+    assert_eq!(decompress(&compressed).unwrap(), input);
+}
+
+#[test]
+fn a_zero_block_size_is_rejected() {
+    assert!(compress_with(b"abc", &Config::DEFAULT.with_block_size(0)).is_err());
+}
+
+#[test]
+fn counts_past_u32_build_codes() {
+    let freq_map: HashMap<u8, u64> =
+        HashMap::from([(b'a', 5 << 32), (b'b', 3 << 32), (b'c', 1), (b'd', 1)]);
+    let lengths = code_lengths(&freq_map, 15).unwrap();
+
+    assert_eq!(lengths[b'a' as usize], 1);
+    assert_eq!(lengths[b'b' as usize], 2);
+    assert_eq!(lengths[b'c' as usize], 3);
+    assert_eq!(lengths[b'd' as usize], 3);
+}
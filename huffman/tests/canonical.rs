@@ -0,0 +1,60 @@
+use huffman::{compress, compress_with, decompress, Config};
+
+const INPUT: &[u8] = b"abracadabra, a canonical example";
+
+// the same bytes on every run and machine, whatever order the frequency map iterates in
+#[rustfmt::skip]
+const STORED: [u8; 95] = [
+    // magic, mode, original length, block size
+    0x48, 0x55, 0x46, 0x00,
+    0x20, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00,
+    // a stored table, the bitmap of the symbols and their code lengths
+    0x00,
+    0x00, 0x00, 0x00, 0x00, 0x01, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x3e, 0xf2, 0x05, 0x01,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x04, 0x05, 0x02, 0x04, 0x03, 0x05, 0x04, 0x05, 0x04, 0x05, 0x04, 0x05, 0x05, 0x04, 0x04,
+    // the data's length and the data
+    0x0f, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x1e, 0xc4, 0x6c, 0x7b, 0x34, 0xc3, 0x22, 0xbd, 0x5c, 0x44, 0xb4, 0x61, 0xdf, 0xcc, 0x00,
+    // CRC-32 of the input
+    0x58, 0xf7, 0xef, 0x46,
+];
+
+#[rustfmt::skip]
+const PRESET: [u8; 54] = [
+    0x48, 0x55, 0x46, 0x00,
+    0x20, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00,
+    // the english preset
+    0x02, 0x00,
+    0x14, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x4e, 0x98, 0x4c, 0x92, 0xc9, 0xd3, 0x09, 0xda, 0x10, 0x64, 0x8f, 0x0e, 0xd9, 0x25, 0xc1, 0xf8,
+    0xa6, 0xbb, 0x5c, 0x80,
+    0x58, 0xf7, 0xef, 0x46,
+];
+
+#[test]
+fn output_matches_the_golden_bytes() {
+    let config = Config::DEFAULT.with_presets(false);
+
+    assert_eq!(compress_with(INPUT, &config).unwrap(), STORED);
+    assert_eq!(compress(INPUT).unwrap(), PRESET);
+
+    assert_eq!(decompress(&STORED).unwrap(), INPUT);
+    assert_eq!(decompress(&PRESET).unwrap(), INPUT);
+}
+
+#[test]
+fn compressing_twice_gives_the_same_bytes() {
+    // every frequency map gets its own random hash seed, so each run iterates differently
+    let input: Vec<u8> = (0..=255u8)
+        .flat_map(|b| std::iter::repeat_n(b, b as usize % 7 + 1))
+        .collect();
+    let config = Config::DEFAULT.with_presets(false).with_block_size(300);
+
+    let first = compress_with(&input, &config).unwrap();
+    for _ in 0..10 {
+        assert_eq!(compress_with(&input, &config).unwrap(), first);
+    }
+}
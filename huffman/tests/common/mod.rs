@@ -0,0 +1,32 @@
+// the tests and the benchmark share these, not every one of them uses all of it
+#![allow(dead_code)]
+
+// xorshift, so the data is the same on every run and we don't need a rand dependency
+pub struct Xorshift(u64);
+
+impl Xorshift {
+    // a state of 0 would stay 0
+    pub fn new(seed: u64) -> Self {
+        Xorshift(seed | 1)
+    }
+}
+
+impl Iterator for Xorshift {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        Some(self.0)
+    }
+}
+
+// and'ing a few outputs together skews the bytes towards fewer set bits
+// so the codes have a spread of lengths like real data, instead of all being 8 bits
+pub fn skewed_bytes(len: usize, seed: u64) -> Vec<u8> {
+    Xorshift::new(seed)
+        .take(len)
+        .map(|x| (x & x >> 8 | x >> 16 & x >> 24) as u8)
+        .collect()
+}
@@ -0,0 +1,89 @@
+use huffman::{canonical, compress, compress_with, decompress, read_header, tree, Config};
+
+// the built-in tables win on inputs this small, these are about the stored ones
+fn compress_stored(input: &[u8]) -> Vec<u8> {
+    compress_with(input, &Config::DEFAULT.with_presets(false)).unwrap()
+}
+
+#[test]
+fn empty_input_round_trips() {
+    let compressed = compress(&[]).unwrap();
+
+    let header = read_header(&compressed).unwrap();
+    assert_eq!(header.original_len, 0);
+    assert!(header.blocks.is_empty());
+    assert_eq!(compressed.len(), header.len);
+
+    assert_eq!(decompress(&compressed).unwrap(), Vec::<u8>::new());
+}
+
+#[test]
+fn single_symbol_input_round_trips() {
+    for symbol in [b'a', 0x00, 0xFF] {
+        for len in [1, 7, 8, 9, 1000] {
+            let input = vec![symbol; len];
+            let compressed = compress_stored(&input);
+
+            // a bit per symbol
+            let header = read_header(&compressed).unwrap();
+            assert_eq!(header.blocks[0].lengths[symbol as usize], 1);
+            assert_eq!(compressed.len() - header.len, len.div_ceil(8));
+
+            assert_eq!(decompress(&compressed).unwrap(), input, "{len} of {symbol}");
+        }
+    }
+}
+
+#[test]
+fn single_symbol_tree_walk_decodes() {
+    let input = vec![b'a'; 20];
+    let compressed = compress_stored(&input);
+
+    let header = read_header(&compressed).unwrap();
+    let block = &header.blocks[0];
+    let root = canonical::build_tree(&block.lengths).unwrap();
+
+    assert_eq!(
+        tree::decode(&compressed[block.data.clone()], &root, input.len()),
+        input
+    );
+}
+
+#[test]
+fn two_symbols_get_a_bit_each() {
+    let input = b"abababbbba".to_vec();
+    let compressed = compress_stored(&input);
+
+    let header = read_header(&compressed).unwrap();
+    assert_eq!(header.blocks[0].lengths[b'a' as usize], 1);
+    assert_eq!(header.blocks[0].lengths[b'b' as usize], 1);
+
+    assert_eq!(decompress(&compressed).unwrap(), input);
+}
+
+#[test]
+fn every_symbol_once_round_trips() {
+    let input: Vec<u8> = (0..=255).collect();
+    assert_eq!(decompress(&compress(&input).unwrap()).unwrap(), input);
+}
+
+#[test]
+fn data_without_codes_is_rejected() {
+    // an empty input's header, claiming 5 bytes in the length after the magic and mode
+    let mut compressed = compress(&[]).unwrap();
+    compressed[4] = 5;
+    compressed.push(0);
+
+    assert!(decompress(&compressed).is_err());
+}
+
+#[test]
+fn the_unused_single_symbol_code_is_rejected() {
+    let mut compressed = compress_stored(b"aaaa");
+    let last = compressed.len() - 1;
+
+    // "1" instead of "0"
+    compressed[last] = 0b1000_0000;
+
+    assert!(decompress(&compressed).is_err());
+}
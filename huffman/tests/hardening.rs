@@ -0,0 +1,119 @@
+use huffman::canonical::{validate, write_lengths, MAX_CODE_LEN};
+use huffman::table::DecodeTable;
+use huffman::{compress, compress_adaptive, compress_with, crc32, decompress, Config, Error};
+
+const INPUT: &[u8] =
+    b"it was the best of times, it was the worst of times, it was the age of wisdom";
+
+fn adaptive(input: &[u8]) -> Vec<u8> {
+    let mut compressed = Vec::new();
+    compress_adaptive(input, &mut compressed).unwrap();
+    compressed
+}
+
+fn files() -> Vec<Vec<u8>> {
+    vec![
+        compress(INPUT).unwrap(),
+        compress_with(INPUT, &Config::DEFAULT.with_block_size(20)).unwrap(),
+        compress(b"").unwrap(),
+        compress(b"aaaa").unwrap(),
+        adaptive(INPUT),
+        adaptive(b""),
+    ]
+}
+
+#[test]
+fn crc32_matches_the_check_value() {
+    assert_eq!(crc32::checksum(b"123456789"), 0xCBF43926);
+    assert_eq!(crc32::checksum(b""), 0);
+
+    let mut crc = crc32::Crc32::new();
+    crc.update(b"1234");
+    crc.update(b"56789");
+    assert_eq!(crc.finish(), 0xCBF43926);
+}
+
+#[test]
+fn truncated_files_are_rejected() {
+    for file in files() {
+        for len in 0..file.len() {
+            let result = decompress(&file[..len]);
+            assert!(
+                matches!(result, Err(Error::Truncated(_))),
+                "{len} bytes: {result:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn flipped_bits_never_decode_to_something_else() {
+    for file in files() {
+        let original = decompress(&file).unwrap();
+
+        for bit in 0..file.len() * 8 {
+            let mut corrupted = file.clone();
+            corrupted[bit / 8] ^= 1 << (bit % 8);
+
+            // the padding after the last code isn't read, so flipping it changes nothing
+            if let Ok(decompressed) = decompress(&corrupted) {
+                assert_eq!(decompressed, original, "bit {bit}");
+            }
+        }
+    }
+}
+
+#[test]
+fn a_wrong_checksum_is_caught() {
+    for mut file in [compress(INPUT).unwrap(), adaptive(INPUT)] {
+        let last = file.len() - 1;
+        file[last] ^= 0x80;
+
+        assert!(matches!(decompress(&file), Err(Error::Checksum { .. })));
+    }
+}
+
+#[test]
+fn bad_headers_are_rejected() {
+    assert!(matches!(decompress(b"PK\x03\x04"), Err(Error::NotHuf)));
+    assert!(matches!(decompress(b"HUF\x07"), Err(Error::UnknownMode(7))));
+
+    for mut file in [compress(INPUT).unwrap(), adaptive(INPUT)] {
+        file.push(0);
+        assert!(matches!(decompress(&file), Err(Error::Malformed(_))));
+    }
+
+    // an original length that the data can't hold, which would otherwise be allocated
+    let mut file = compress(INPUT).unwrap();
+    file[4..12].copy_from_slice(&(u64::MAX / 2).to_le_bytes());
+    file[12..20].copy_from_slice(&u64::MAX.to_le_bytes());
+    assert!(matches!(decompress(&file), Err(Error::Malformed(_))));
+}
+
+#[test]
+fn codes_over_the_maximum_length_are_rejected() {
+    // a full tree with one symbol per length, and two at the longest
+    let too_long = MAX_CODE_LEN + 1;
+    let mut lengths = [0; 256];
+    for (symbol, len) in (1..=too_long).chain([too_long]).enumerate() {
+        lengths[symbol] = len;
+    }
+
+    assert!(matches!(validate(&lengths), Err(Error::Malformed(_))));
+    assert!(matches!(
+        DecodeTable::new(&lengths),
+        Err(Error::Malformed(_))
+    ));
+
+    // the same symbols compress to a stored table, which gets swapped for the long one
+    let input: Vec<u8> = (0..=too_long).collect();
+    let mut file = compress_with(&input, &Config::DEFAULT.with_presets(false)).unwrap();
+
+    let mut table = Vec::new();
+    write_lengths(&lengths, &mut table);
+    // after the magic, the mode, both lengths and the block's table byte
+    assert_eq!(file[20], 0, "the block stores its table");
+    file[21..21 + table.len()].copy_from_slice(&table);
+
+    assert!(matches!(decompress(&file), Err(Error::Malformed(_))));
+}
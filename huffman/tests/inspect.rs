@@ -0,0 +1,80 @@
+use huffman::{
+    canonical, code_lengths, compress, compress_with, inspect, read_header,
+    tree::{build_frequency_map, build_huffman_tree},
+    Config,
+};
+
+#[test]
+fn dyadic_frequencies_reach_the_entropy() {
+    // probabilities of 1/2, 1/4, 1/8, 1/8 get codes of exactly -log2(p) bits
+    let input = b"aaaabbcd";
+    let freq_map = build_frequency_map(input);
+    let report = inspect::report(&build_huffman_tree(freq_map.clone()).unwrap(), &freq_map);
+
+    assert_eq!(report.total, 8);
+    assert_eq!(report.symbols[0].symbol, b'a');
+    assert_eq!(report.symbols[0].code.len(), 1);
+    assert!((report.average_len - 1.75).abs() < 1e-9);
+    assert!((report.entropy - 1.75).abs() < 1e-9);
+}
+
+#[test]
+fn stored_codes_get_their_frequencies_back() {
+    let input = b"abracadabra";
+    let freq_map = build_frequency_map(input);
+
+    let compressed = compress(input).unwrap();
+    let header = read_header(&compressed).unwrap();
+    let root = inspect::weigh(
+        &canonical::build_tree(&header.blocks[0].lengths).unwrap(),
+        &freq_map,
+    );
+
+    assert_eq!(root.frequency(), input.len() as u64);
+
+    let report = inspect::report(&root, &freq_map);
+    for symbol in &report.symbols {
+        assert_eq!(
+            symbol.code.len() as u8,
+            header.blocks[0].lengths[symbol.symbol as usize]
+        );
+    }
+}
+
+#[test]
+fn plain_input_gets_the_codes_compress_stores() {
+    // fibonacci frequencies make a tree deeper than the default limit
+    let (mut a, mut b) = (1usize, 1usize);
+    let mut input = Vec::new();
+    for symbol in 0..25u8 {
+        input.extend(std::iter::repeat_n(symbol, a));
+        (a, b) = (b, a + b);
+    }
+
+    let freq_map = build_frequency_map(&input);
+    let lengths = code_lengths(&freq_map, Config::DEFAULT.max_code_len).unwrap();
+    let root = inspect::weigh(&canonical::build_tree(&lengths).unwrap(), &freq_map);
+    let report = inspect::report(&root, &freq_map);
+
+    let compressed = compress_with(&input, &Config::DEFAULT.with_presets(false)).unwrap();
+    let stored = read_header(&compressed).unwrap().blocks[0].lengths;
+    let codes = canonical::codes(&stored);
+
+    assert!(report.symbols.iter().any(|symbol| symbol.code.len() == 15));
+    for symbol in &report.symbols {
+        assert_eq!(symbol.code, codes[&symbol.symbol]);
+    }
+}
+
+#[test]
+fn dot_has_a_node_for_every_symbol() {
+    let input = b"hello \"dot\" \\ world";
+    let freq_map = build_frequency_map(input);
+    let dot = inspect::to_dot(&build_huffman_tree(freq_map.clone()).unwrap());
+
+    assert!(dot.starts_with("digraph huffman {"));
+    assert_eq!(dot.matches("shape=box").count(), freq_map.len());
+    assert_eq!(dot.matches(" -> ").count(), 2 * freq_map.len() - 2);
+    assert!(dot.contains(r#"label="\"\n2""#));
+    assert!(dot.contains(r#"label="\\\n1""#));
+}
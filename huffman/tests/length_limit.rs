@@ -0,0 +1,127 @@
+use huffman::canonical::{self, limited_code_lengths, validate, Lengths, MAX_CODE_LEN};
+use huffman::tree::build_huffman_tree;
+use huffman::{code_lengths, compress_with, decompress, read_header, Config};
+use std::collections::HashMap;
+
+// 1, 1, 2, 3, 5, 8, ... the distribution that makes the deepest huffman trees
+fn fibonacci(symbols: usize) -> HashMap<u8, u64> {
+    let (mut a, mut b) = (1, 1);
+
+    (0..symbols as u8)
+        .map(|symbol| {
+            let frequency = a;
+            (a, b) = (b, a + b);
+            (symbol, frequency)
+        })
+        .collect()
+}
+
+fn cost(freq_map: &HashMap<u8, u64>, lengths: &Lengths) -> u64 {
+    freq_map
+        .iter()
+        .map(|(&symbol, &frequency)| frequency * lengths[symbol as usize] as u64)
+        .sum()
+}
+
+#[test]
+fn fibonacci_trees_are_as_deep_as_there_are_symbols() {
+    let freq_map = fibonacci(30);
+    let lengths = canonical::code_lengths(&build_huffman_tree(freq_map).unwrap());
+
+    assert_eq!(lengths.iter().max(), Some(&29));
+}
+
+#[test]
+fn limited_lengths_stay_under_the_limit() {
+    let freq_map = fibonacci(30);
+    let huffman = canonical::code_lengths(&build_huffman_tree(freq_map.clone()).unwrap());
+    let mut previous_cost = cost(&freq_map, &huffman);
+
+    for max_len in [MAX_CODE_LEN, 15, 12, 8, 5] {
+        let lengths = limited_code_lengths(&freq_map, max_len).unwrap();
+
+        assert!(lengths.iter().all(|&len| len <= max_len), "limit {max_len}");
+        assert!(freq_map.keys().all(|&symbol| lengths[symbol as usize] > 0));
+        validate(&lengths).unwrap();
+
+        // a tighter limit can only cost more
+        let cost = cost(&freq_map, &lengths);
+        assert!(cost >= previous_cost, "limit {max_len}");
+        previous_cost = cost;
+    }
+}
+
+#[test]
+fn limited_lengths_match_huffman_when_the_limit_is_loose() {
+    let freq_map = fibonacci(20);
+
+    let huffman = code_lengths(&freq_map, MAX_CODE_LEN).unwrap();
+    let limited = limited_code_lengths(&freq_map, 19).unwrap();
+
+    assert_eq!(cost(&freq_map, &limited), cost(&freq_map, &huffman));
+}
+
+#[test]
+fn limited_lengths_are_optimal() {
+    let freq_map = fibonacci(6);
+    let max_len = 3;
+
+    // every way of giving the 6 symbols lengths of 1 to 3 that makes a full tree
+    let best = (0..3u32.pow(6))
+        .map(|combination| {
+            let mut lengths = [0; 256];
+            for (symbol, length) in lengths.iter_mut().take(6).enumerate() {
+                *length = (combination / 3u32.pow(symbol as u32) % 3) as u8 + 1;
+            }
+            lengths
+        })
+        .filter(|lengths| validate(lengths).is_ok())
+        .map(|lengths| cost(&freq_map, &lengths))
+        .min()
+        .unwrap();
+
+    let lengths = limited_code_lengths(&freq_map, max_len).unwrap();
+    assert_eq!(cost(&freq_map, &lengths), best);
+}
+
+#[test]
+fn the_limit_has_to_fit_every_symbol() {
+    let freq_map: HashMap<u8, u64> = (0..=255).map(|symbol| (symbol, 1)).collect();
+
+    assert!(limited_code_lengths(&freq_map, 7).is_err());
+
+    let lengths = limited_code_lengths(&freq_map, 8).unwrap();
+    assert!(lengths.iter().all(|&len| len == 8));
+}
+
+#[test]
+fn limited_files_round_trip() {
+    let input: Vec<u8> = fibonacci(25)
+        .into_iter()
+        .flat_map(|(symbol, frequency)| std::iter::repeat_n(symbol, frequency as usize))
+        .collect();
+
+    for max_len in [15, 12] {
+        let compressed =
+            compress_with(&input, &Config::DEFAULT.with_max_code_len(max_len)).unwrap();
+
+        let header = read_header(&compressed).unwrap();
+        assert!(header
+            .blocks
+            .iter()
+            .all(|block| block.lengths.iter().all(|&len| len <= max_len)));
+
+        assert_eq!(decompress(&compressed).unwrap(), input);
+    }
+}
+
+#[test]
+fn limits_out_of_range_are_errors() {
+    for max_len in [0, MAX_CODE_LEN + 1] {
+        let config = Config::DEFAULT.with_max_code_len(max_len);
+        assert!(compress_with(b"aaaa", &config).is_err(), "limit {max_len}");
+    }
+
+    let compressed = compress_with(b"aaaa", &Config::DEFAULT.with_max_code_len(1)).unwrap();
+    assert_eq!(decompress(&compressed).unwrap(), b"aaaa");
+}
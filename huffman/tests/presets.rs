@@ -0,0 +1,108 @@
+use huffman::{
+    canonical::validate,
+    compress_with, decompress, decompress_with,
+    presets::{self, Preset},
+    read_header_with, Config, Error, TableSource,
+};
+
+const MESSAGE: &[u8] = b"Meet me at the station at noon, and bring the map we talked about.";
+const JSON: &[u8] = br#"{"id": 1042, "name": "widget", "tags": ["blue", "small"], "price": 9.99}"#;
+
+fn table(input: &[u8], config: &Config) -> TableSource {
+    let compressed = compress_with(input, config).unwrap();
+    assert_eq!(
+        decompress_with(&compressed, config.custom_table.as_ref()).unwrap(),
+        input
+    );
+
+    read_header_with(&compressed, config.custom_table.as_ref())
+        .unwrap()
+        .blocks[0]
+        .table
+}
+
+#[test]
+fn presets_have_a_code_for_every_byte() {
+    for preset in Preset::ALL {
+        let lengths = preset.lengths();
+
+        validate(lengths).unwrap();
+        assert!(
+            lengths.iter().all(|&len| (1..=15).contains(&len)),
+            "{}",
+            preset.name()
+        );
+    }
+
+    assert!(Preset::Flat.lengths().iter().all(|&len| len == 8));
+}
+
+#[test]
+fn small_messages_use_a_preset() {
+    assert_eq!(
+        table(MESSAGE, &Config::DEFAULT),
+        TableSource::Preset(Preset::English)
+    );
+    assert_eq!(
+        table(JSON, &Config::DEFAULT),
+        TableSource::Preset(Preset::Json)
+    );
+
+    let with = compress_with(MESSAGE, &Config::DEFAULT).unwrap();
+    let without = compress_with(MESSAGE, &Config::DEFAULT.with_presets(false)).unwrap();
+    assert!(
+        with.len() < without.len(),
+        "{} against {}",
+        with.len(),
+        without.len()
+    );
+}
+
+#[test]
+fn presets_over_the_length_limit_are_skipped() {
+    let config = Config::DEFAULT.with_max_code_len(9);
+    assert!(!matches!(
+        table(MESSAGE, &config),
+        TableSource::Preset(Preset::English)
+    ));
+}
+
+#[test]
+fn custom_tables_round_trip() {
+    let custom = presets::table_from_sample(&MESSAGE.repeat(10), 15).unwrap();
+    let config = Config::DEFAULT
+        .with_presets(false)
+        .with_custom_table(custom);
+
+    let checksum = presets::checksum(&custom);
+    assert_eq!(table(MESSAGE, &config), TableSource::Custom(checksum));
+
+    let compressed = compress_with(MESSAGE, &config).unwrap();
+    assert!(matches!(
+        decompress(&compressed),
+        Err(Error::CustomTable { expected, given: None }) if expected == checksum
+    ));
+    assert!(matches!(
+        decompress_with(&compressed, Some(Preset::Flat.lengths())),
+        Err(Error::CustomTable { given: Some(_), .. })
+    ));
+}
+
+#[test]
+fn table_files_round_trip() {
+    let custom = presets::table_from_sample(JSON, 12).unwrap();
+    let file = presets::write_table(&custom);
+
+    assert_eq!(presets::read_table(&file).unwrap(), custom);
+
+    let mut longer = file.clone();
+    longer.push(0);
+    assert!(matches!(
+        presets::read_table(&longer),
+        Err(Error::Malformed(_))
+    ));
+    assert!(matches!(
+        presets::read_table(&file[..40]),
+        Err(Error::Truncated(_))
+    ));
+}
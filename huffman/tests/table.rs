@@ -0,0 +1,95 @@
+mod common;
+
+use common::Xorshift;
+use huffman::canonical::{self, limited_code_lengths, Lengths, MAX_CODE_LEN};
+use huffman::table::DecodeTable;
+use huffman::tree;
+use std::collections::HashMap;
+
+// every symbol with a code, a few times each, in a scrambled order
+fn message(lengths: &Lengths) -> Vec<u8> {
+    let mut symbols: Vec<u8> = (0..=255u8)
+        .filter(|&symbol| lengths[symbol as usize] != 0)
+        .flat_map(|symbol| std::iter::repeat_n(symbol, 5))
+        .collect();
+
+    let shuffle = (1..symbols.len())
+        .rev()
+        .zip(Xorshift::new(0x9E37_79B9_7F4A_7C15));
+    for (i, random) in shuffle {
+        symbols.swap(i, (random % (i as u64 + 1)) as usize);
+    }
+
+    symbols
+}
+
+fn tables() -> Vec<Lengths> {
+    // one code of every length, the deepest tree there can be
+    let mut chain = [0; 256];
+    for (symbol, len) in (1..=MAX_CODE_LEN).chain([MAX_CODE_LEN]).enumerate() {
+        chain[symbol] = len;
+    }
+
+    // every byte, the frequencies doubling every 16 symbols
+    let doubling: HashMap<u8, u64> = (0..=255u8).map(|b| (b, 1 << (b / 16))).collect();
+
+    // 1, 1, 2, 3, 5, ... cut down to the longest code allowed
+    let (mut a, mut b) = (1u64, 1u64);
+    let fibonacci: HashMap<u8, u64> = (0..40u8)
+        .map(|symbol| {
+            let frequency = a;
+            (a, b) = (b, a + b);
+            (symbol, frequency)
+        })
+        .collect();
+
+    vec![
+        chain,
+        limited_code_lengths(&doubling, MAX_CODE_LEN).unwrap(),
+        limited_code_lengths(&doubling, 12).unwrap(),
+        limited_code_lengths(&fibonacci, MAX_CODE_LEN).unwrap(),
+    ]
+}
+
+#[test]
+fn matches_the_tree_walk() {
+    for lengths in tables() {
+        let input = message(&lengths);
+        let encoded = tree::encode(&input, &canonical::codes(&lengths));
+
+        let root = canonical::build_tree(&lengths).unwrap();
+        let table = DecodeTable::new(&lengths).unwrap();
+
+        assert_eq!(tree::decode(&encoded, &root, input.len()), input);
+        assert_eq!(table.decode(&encoded, input.len()).unwrap(), input);
+    }
+}
+
+#[test]
+fn the_tables_mix_short_and_long_codes() {
+    for lengths in tables() {
+        let used = lengths.iter().filter(|&&len| len != 0);
+
+        // the table looks up 10 bits at a time, longer codes go through a second table
+        assert!(used.clone().any(|&len| len <= 10));
+        assert!(used.clone().any(|&len| len > 10));
+        assert!(used.clone().all(|&len| len <= MAX_CODE_LEN));
+    }
+
+    assert!(tables()
+        .iter()
+        .any(|lengths| lengths.contains(&MAX_CODE_LEN)));
+}
+
+#[test]
+fn every_long_code_decodes_on_its_own() {
+    for lengths in tables() {
+        let table = DecodeTable::new(&lengths).unwrap();
+        let codes = canonical::codes(&lengths);
+
+        for (&symbol, code) in codes.iter().filter(|(_, code)| code.len() > 10) {
+            let encoded = tree::encode(&[symbol], &codes);
+            assert_eq!(table.decode(&encoded, 1).unwrap(), [symbol], "{code:?}");
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use base64::hex;
 use std::{io::Read as _, ops::RangeInclusive};
 
 const A: u32 = 0x67452301u32;
@@ -128,13 +129,12 @@ fn create_md5_digest(mut v: Vec<u8>) -> String {
         d = D.wrapping_add(d);
     }
 
-    format!(
-        "{:08x}{:08x}{:08x}{:08x}",
-        a.swap_bytes(),
-        b.swap_bytes(),
-        c.swap_bytes(),
-        d.swap_bytes()
-    )
+    let digest: Vec<u8> = [a, b, c, d]
+        .into_iter()
+        .flat_map(u32::to_le_bytes)
+        .collect();
+
+    hex::encode(&digest)
 }
 
 fn main() {